@@ -0,0 +1,293 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    catalog::{Catalog, ItemAmount},
+    patterns::{
+        Blueprint,
+        body::{ObjectHeaderType, ObjectType, class_name},
+        header::Resource,
+    },
+};
+
+/// Recipe a building was built with
+const BUILT_WITH_RECIPE: &str = "mBuiltWithRecipe";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildingCount {
+    pub type_path: String,
+    pub count: usize,
+}
+
+/// Cost of every building built with one recipe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeCost {
+    pub recipe: String,
+    /// Number of buildings built with the recipe
+    pub count: usize,
+    /// Total ingredients, `None` when the recipe is missing from the catalog
+    pub items: Option<Vec<ItemAmount>>,
+}
+
+/// An item whose cost differs from the resource list stored in the blueprint header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceMismatch {
+    /// Class name of the item descriptor
+    pub item: String,
+    pub computed: f64,
+    pub stored: u32,
+}
+
+/// Buildings of a blueprint and what they cost to place. Costs are the recipe ingredients of
+/// each building, so buildings priced by length such as belts, pipes and wires show up as
+/// mismatches with the stored resource list.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BillOfMaterials {
+    /// Buildings by type path, sorted by type path
+    pub buildings: Vec<BuildingCount>,
+    /// Costs by recipe path, sorted by recipe path
+    pub recipes: Vec<RecipeCost>,
+    /// Total cost of the blueprint by item class name
+    pub items: Vec<ItemAmount>,
+    /// Items whose total differs from [`crate::patterns::header::ResourceList`]
+    pub mismatches: Vec<ResourceMismatch>,
+}
+
+impl BillOfMaterials {
+    /// Counts the buildings of `blueprint` and prices them with the recipes in `catalog`.
+    /// Buildings without an `mBuiltWithRecipe` reference are priced with the build gun recipe of
+    /// their type, see [`Catalog::building_recipe`].
+    pub fn new(blueprint: &Blueprint, catalog: &Catalog) -> Self {
+        let body = &blueprint.body;
+        let mut buildings = BTreeMap::<&str, usize>::new();
+        let mut recipes = BTreeMap::<String, usize>::new();
+
+        for (header, object) in body.object_headers.iter().zip(&body.objects) {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) = (header, object)
+            else {
+                continue;
+            };
+            *buildings.entry(&header.type_path).or_default() += 1;
+
+            let recipe = actor
+                .get::<&str>(BUILT_WITH_RECIPE)
+                .map(ToOwned::to_owned)
+                .or_else(|| {
+                    catalog
+                        .building_recipe(&header.type_path)
+                        .map(|recipe| recipe.class_name.clone())
+                });
+            if let Some(recipe) = recipe {
+                *recipes.entry(recipe).or_default() += 1;
+            }
+        }
+
+        let mut items = BTreeMap::<String, f64>::new();
+        let recipes: Vec<_> = recipes
+            .into_iter()
+            .map(|(recipe, count)| {
+                let costs = catalog.recipe(&recipe).map(|data| {
+                    data.ingredients
+                        .iter()
+                        .map(|ingredient| ItemAmount {
+                            item: ingredient.item.clone(),
+                            amount: ingredient.amount * count as f64,
+                        })
+                        .collect::<Vec<_>>()
+                });
+                for cost in costs.iter().flatten() {
+                    *items.entry(cost.item.clone()).or_default() += cost.amount;
+                }
+
+                RecipeCost {
+                    recipe,
+                    count,
+                    items: costs,
+                }
+            })
+            .collect();
+
+        let mut stored = BTreeMap::<&str, u32>::new();
+        for resource in &blueprint.header.resource_list.resources {
+            *stored.entry(class_name(resource.path)).or_default() += resource.count;
+        }
+        let mut mismatches: Vec<_> = items
+            .iter()
+            .map(|(item, &computed)| (item.as_str(), computed))
+            .chain(
+                stored
+                    .keys()
+                    .filter(|item| !items.contains_key(**item))
+                    .map(|item| (*item, 0.0)),
+            )
+            .filter_map(|(item, computed)| {
+                let stored = stored.get(item).copied().unwrap_or_default();
+                (computed != f64::from(stored)).then(|| ResourceMismatch {
+                    item: item.to_owned(),
+                    computed,
+                    stored,
+                })
+            })
+            .collect();
+        mismatches.sort_by(|a, b| a.item.cmp(&b.item));
+
+        BillOfMaterials {
+            buildings: buildings
+                .into_iter()
+                .map(|(type_path, count)| BuildingCount {
+                    type_path: type_path.to_owned(),
+                    count,
+                })
+                .collect(),
+            recipes,
+            items: items
+                .into_iter()
+                .map(|(item, amount)| ItemAmount { item, amount })
+                .collect(),
+            mismatches,
+        }
+    }
+
+    /// Writes the bill as CSV with the columns `kind,class,name,amount,stored`. Buildings come
+    /// first with their count, then items with their computed and stored amounts. Names come
+    /// from `catalog` and are empty when missing.
+    pub fn to_csv(&self, catalog: &Catalog) -> String {
+        let name = |path: &str| csv_field(catalog.display_name(path).unwrap_or_default());
+        let mut csv = String::from("kind,class,name,amount,stored\n");
+
+        for building in &self.buildings {
+            let _ = writeln!(
+                csv,
+                "building,{},{},{},",
+                csv_field(class_name(&building.type_path)),
+                name(&building.type_path),
+                building.count
+            );
+        }
+
+        let stored = |item: &str| {
+            self.mismatches
+                .iter()
+                .find(|mismatch| mismatch.item == item)
+                .map(|mismatch| mismatch.stored)
+        };
+        for item in &self.items {
+            let stored = stored(&item.item).map_or(item.amount, f64::from);
+            let _ = writeln!(
+                csv,
+                "item,{},{},{},{stored}",
+                csv_field(&item.item),
+                name(&item.item),
+                item.amount
+            );
+        }
+        for mismatch in &self.mismatches {
+            if mismatch.computed == 0.0 {
+                let _ = writeln!(
+                    csv,
+                    "item,{},{},0,{}",
+                    csv_field(&mismatch.item),
+                    name(&mismatch.item),
+                    mismatch.stored
+                );
+            }
+        }
+
+        csv
+    }
+}
+
+impl<'header> Blueprint<'header> {
+    /// Replaces the resource list with the cost of the buildings according to `catalog`, see
+    /// [`BillOfMaterials`]. Amounts are rounded up to whole items. Returns the recipes missing from
+    /// the catalog, whose buildings are left out of the cost.
+    pub fn recompute_resources(&mut self, catalog: &'header Catalog) -> Vec<String> {
+        let bill = BillOfMaterials::new(self, catalog);
+
+        self.header.resource_list.resources = bill
+            .items
+            .iter()
+            .filter_map(|amount| {
+                Some(Resource {
+                    path: catalog.item_path(&amount.item)?,
+                    count: amount.amount.ceil() as u32,
+                })
+            })
+            .collect();
+
+        bill.recipes
+            .into_iter()
+            .filter(|cost| cost.items.is_none())
+            .map(|cost| cost.recipe)
+            .collect()
+    }
+}
+
+/// Quotes a CSV field if it needs to be.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCS: &str = include_str!("../Docs Sample.json");
+
+    #[test]
+    fn check_bill_of_materials() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+        let bom = BillOfMaterials::new(&blueprint, &catalog);
+
+        let generators = bom
+            .buildings
+            .iter()
+            .find(|building| building.type_path.contains("Build_GeneratorCoal_C"))
+            .expect("Generators should be counted");
+        assert_eq!(generators.count, 3);
+        assert_eq!(
+            bom.buildings
+                .iter()
+                .map(|building| building.count)
+                .sum::<usize>(),
+            25
+        );
+
+        let cost = bom
+            .recipes
+            .iter()
+            .find(|cost| cost.recipe.contains("Recipe_GeneratorCoal_C"))
+            .expect("Generator recipe should be counted");
+        assert_eq!(cost.count, 3);
+        assert_eq!(cost.items.as_ref().map(Vec::len), Some(3));
+
+        // Only the generator recipe is in the sample catalog and its costs agree with the header
+        let mismatch = |item| bom.mismatches.iter().find(|mismatch| mismatch.item == item);
+        assert_eq!(mismatch("Desc_Rotor_C"), None);
+        assert_eq!(
+            mismatch("Desc_Cable_C"),
+            Some(&ResourceMismatch {
+                item: "Desc_Cable_C".to_owned(),
+                computed: 90.0,
+                stored: 105,
+            })
+        );
+        assert_eq!(
+            mismatch("Desc_Wire_C").map(|mismatch| mismatch.computed),
+            Some(0.0)
+        );
+
+        let csv = bom.to_csv(&catalog);
+        assert!(csv.starts_with("kind,class,name,amount,stored\n"));
+        assert!(csv.contains("building,Build_GeneratorCoal_C,Coal-Powered Generator,3,\n"));
+        assert!(csv.contains("item,Desc_Rotor_C,Rotor,30,30\n"));
+        assert!(csv.contains("item,Desc_Cable_C,Cable,90,105\n"));
+        assert!(csv.contains("item,Desc_Wire_C,Wire,0,3\n"));
+    }
+}
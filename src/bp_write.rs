@@ -70,6 +70,18 @@ impl<W: Write> BPWrite<W> for u64 {
     }
 }
 
+impl<W: Write> BPWrite<W> for i32 {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        writer.write_all(self.to_le_bytes().as_slice())
+    }
+}
+
+impl<W: Write> BPWrite<W> for f64 {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        writer.write_all(self.to_le_bytes().as_slice())
+    }
+}
+
 impl<W: Write> BPWrite<W> for f32 {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
         writer.write_all(self.to_le_bytes().as_slice())
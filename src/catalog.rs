@@ -0,0 +1,446 @@
+use std::{collections::HashMap, path::Path};
+
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+use serde_json::Value;
+use winnow::{
+    Parser,
+    ascii::float,
+    combinator::{delimited, separated},
+    token::{take_till, take_until},
+};
+
+use crate::patterns::body::class_name;
+
+/// Native classes of recipes in `Docs.json`
+const RECIPE_CLASS: &str = "FGRecipe'";
+/// Native classes of buildings all start with this
+const BUILDABLE_CLASS: &str = "FGBuildable";
+
+/// An amount of an item, as used by recipes and blueprint costs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemAmount {
+    /// Class name of the item descriptor, e.g. `Desc_SteelPlate_C`
+    pub item: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub class_name: String,
+    pub display_name: String,
+    /// Megajoules released by burning one item, or one litre of a fluid
+    pub energy_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeData {
+    pub class_name: String,
+    pub display_name: String,
+    pub ingredients: Vec<ItemAmount>,
+    pub products: Vec<ItemAmount>,
+    /// Seconds to craft the recipe once
+    pub duration: f64,
+    /// Class names of the buildings or tools that craft the recipe
+    pub produced_in: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Building {
+    pub class_name: String,
+    pub display_name: String,
+    /// Megawatts used when running at 100%
+    pub power_consumption: Option<f64>,
+    /// Megawatts produced when running at 100%
+    pub power_production: Option<f64>,
+    /// How power consumption grows with the clock speed, `consumption * clock ^ exponent`
+    pub power_consumption_exponent: Option<f64>,
+    /// Width, depth and height in centimetres, for buildings that snap to a grid
+    pub footprint: Option<[f64; 3]>,
+    /// Fuels a generator burns, the first being the one it starts with
+    pub fuels: Vec<Fuel>,
+    /// Litres of the supplemental resource of a fuel, such as water, used per second for each
+    /// megawatt produced
+    pub supplemental_to_power_ratio: Option<f64>,
+}
+
+/// A fuel of a generator, with what it uses and leaves behind while burning it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fuel {
+    /// Class name of the fuel item
+    pub item: String,
+    /// Class name of the resource used alongside the fuel
+    pub supplemental: Option<String>,
+    /// Item left behind for each fuel item burnt, such as nuclear waste
+    pub byproduct: Option<ItemAmount>,
+}
+
+/// Buildings, recipes and item descriptors of the game, loaded from the `Docs.json` file found
+/// in the `CommunityResources/Docs` folder of the game. Lookups accept either full paths such as
+/// `/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C` or class
+/// names, with or without the trailing nul.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Catalog {
+    items: HashMap<String, Item>,
+    recipes: HashMap<String, RecipeData>,
+    buildings: HashMap<String, Building>,
+    /// Full path of every item used by a recipe, with the trailing nul, by class name
+    item_paths: HashMap<String, String>,
+}
+
+/// One ingredient or product, e.g.
+/// `(ItemClass="/Script/Engine.BlueprintGeneratedClass'/Game/.../Desc_SteelPlate.Desc_SteelPlate_C'",Amount=2)`.
+/// Older exports quote the path differently, so only the path itself is looked for. Returns the
+/// path along with the amount.
+fn item_amount<'i>(input: &mut &'i str) -> winnow::Result<(&'i str, ItemAmount)> {
+    take_until(0.., "/Game/").parse_next(input)?;
+    let path = take_till(1.., ['\'', '"']).parse_next(input)?;
+    (take_until(0.., "Amount="), "Amount=").parse_next(input)?;
+    let amount = float.parse_next(input)?;
+    ')'.parse_next(input)?;
+
+    Ok((
+        path,
+        ItemAmount {
+            item: class_name(path).to_owned(),
+            amount,
+        },
+    ))
+}
+
+fn item_amounts<'i>(input: &mut &'i str) -> winnow::Result<Vec<(&'i str, ItemAmount)>> {
+    delimited('(', separated(0.., item_amount, ','), ')').parse_next(input)
+}
+
+/// Class names in a list such as `("/Game/.../Build_SmelterMk1.Build_SmelterMk1_C","/Script/FactoryGame.FGBuildGun")`.
+fn class_names(list: &str) -> Vec<String> {
+    list.trim_matches(['(', ')'])
+        .split(',')
+        .map(|path| path.trim_matches('"'))
+        .filter(|path| !path.is_empty())
+        .map(|path| class_name(path).to_owned())
+        .collect()
+}
+
+/// Decodes `Docs.json`, which the game writes as UTF-16 with a byte order mark.
+fn decode(data: &[u8]) -> Result<String> {
+    match data {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            Ok(String::from_utf16(&units)?)
+        }
+        [0xFE, 0xFF, rest @ ..] => {
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            Ok(String::from_utf16(&units)?)
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => Ok(String::from_utf8(rest.to_vec())?),
+        data => Ok(String::from_utf8(data.to_vec())?),
+    }
+}
+
+/// Fields of a class in `Docs.json`, which are all strings.
+struct Class<'j>(&'j serde_json::Map<String, Value>);
+
+impl Class<'_> {
+    fn string(&self, field: &str) -> Option<&str> {
+        self.0.get(field)?.as_str()
+    }
+
+    fn number(&self, field: &str) -> Option<f64> {
+        self.string(field)?.trim().parse().ok()
+    }
+
+    fn required(&self, field: &str) -> Result<&str> {
+        self.string(field)
+            .ok_or_else(|| eyre!("Class is missing the {field:?} field"))
+    }
+
+    /// Fuels of a generator, which `Docs.json` lists as objects rather than a string.
+    fn fuels(&self) -> Vec<Fuel> {
+        let Some(Value::Array(fuels)) = self.0.get("mFuel") else {
+            return Vec::new();
+        };
+
+        fuels
+            .iter()
+            .filter_map(|fuel| {
+                let fuel = Class(fuel.as_object()?);
+                let class = |field| {
+                    fuel.string(field)
+                        .filter(|path| !path.is_empty())
+                        .map(|path| class_name(path).to_owned())
+                };
+
+                Some(Fuel {
+                    item: class("mFuelClass")?,
+                    supplemental: class("mSupplementalResourceClass"),
+                    byproduct: class("mByproduct").map(|item| ItemAmount {
+                        item,
+                        amount: fuel.number("mByproductAmount").unwrap_or_default(),
+                    }),
+                })
+            })
+            .collect()
+    }
+
+    fn amounts(&self, field: &str) -> Result<Vec<(&str, ItemAmount)>> {
+        let list = self.string(field).unwrap_or_default();
+        if list.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        item_amounts
+            .parse(list)
+            .map_err(|error| eyre!("Invalid item list in {field:?}: {error}"))
+    }
+}
+
+impl Catalog {
+    /// Reads a catalog from the contents of `Docs.json`, in UTF-8 or UTF-16.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_json(&decode(data)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let Value::Array(groups) = serde_json::from_str(json)? else {
+            bail!("Expected a list of native classes");
+        };
+
+        let mut catalog = Catalog::default();
+        for group in &groups {
+            let native_class = group
+                .get("NativeClass")
+                .and_then(Value::as_str)
+                .ok_or_else(|| eyre!("Native class group is missing its name"))?;
+            let classes = group
+                .get("Classes")
+                .and_then(Value::as_array)
+                .ok_or_else(|| eyre!("Native class {native_class:?} is missing its classes"))?;
+
+            for class in classes {
+                let class = Class(
+                    class
+                        .as_object()
+                        .ok_or_else(|| eyre!("Expected a class in {native_class:?}"))?,
+                );
+                catalog.add(native_class, &class)?;
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    fn add(&mut self, native_class: &str, class: &Class) -> Result<()> {
+        let class_name = class.required("ClassName")?.to_owned();
+        let display_name = class.string("mDisplayName").unwrap_or_default().to_owned();
+
+        if native_class.ends_with(RECIPE_CLASS) {
+            let mut amounts = |field| -> Result<Vec<ItemAmount>> {
+                let amounts = class.amounts(field)?;
+                for (path, amount) in &amounts {
+                    self.item_paths
+                        .entry(amount.item.clone())
+                        .or_insert_with(|| format!("{path}\0"));
+                }
+
+                Ok(amounts.into_iter().map(|(_, amount)| amount).collect())
+            };
+            let recipe = RecipeData {
+                class_name: class_name.clone(),
+                display_name,
+                ingredients: amounts("mIngredients")?,
+                products: amounts("mProduct")?,
+                duration: class.number("mManufactoringDuration").unwrap_or_default(),
+                produced_in: class_names(class.string("mProducedIn").unwrap_or_default()),
+            };
+            self.recipes.insert(class_name, recipe);
+        } else if native_class.contains(BUILDABLE_CLASS) {
+            let footprint = match ["mWidth", "mDepth", "mHeight"].map(|field| class.number(field)) {
+                [Some(width), Some(depth), Some(height)] => Some([width, depth, height]),
+                _ => None,
+            };
+            let building = Building {
+                class_name: class_name.clone(),
+                display_name,
+                power_consumption: class.number("mPowerConsumption"),
+                power_production: class.number("mPowerProduction"),
+                power_consumption_exponent: class.number("mPowerConsumptionExponent"),
+                footprint,
+                fuels: class.fuels(),
+                supplemental_to_power_ratio: class.number("mSupplementalToPowerRatio"),
+            };
+            self.buildings.insert(class_name, building);
+        } else if class.string("mStackSize").is_some() {
+            let item = Item {
+                class_name: class_name.clone(),
+                display_name,
+                energy_value: class.number("mEnergyValue"),
+            };
+            self.items.insert(class_name, item);
+        }
+
+        Ok(())
+    }
+
+    pub fn item(&self, path: &str) -> Option<&Item> {
+        self.items.get(class_name(path))
+    }
+
+    pub fn recipe(&self, path: &str) -> Option<&RecipeData> {
+        self.recipes.get(class_name(path))
+    }
+
+    /// Full path of an item with the trailing nul, as stored in blueprint resource lists. Only
+    /// the paths of items used by a recipe are known.
+    pub fn item_path(&self, item: &str) -> Option<&str> {
+        self.item_paths.get(class_name(item)).map(String::as_str)
+    }
+
+    pub fn building(&self, path: &str) -> Option<&Building> {
+        self.buildings.get(class_name(path))
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items.values()
+    }
+
+    pub fn recipes(&self) -> impl Iterator<Item = &RecipeData> {
+        self.recipes.values()
+    }
+
+    pub fn buildings(&self) -> impl Iterator<Item = &Building> {
+        self.buildings.values()
+    }
+
+    /// The recipe the build gun uses for a building, whose product is the building's descriptor:
+    /// `Desc_ConveyorBeltMk1_C` for `Build_ConveyorBeltMk1_C`.
+    pub fn building_recipe(&self, type_path: &str) -> Option<&RecipeData> {
+        let descriptor = class_name(type_path).replacen("Build_", "Desc_", 1);
+
+        self.recipes.values().find(|recipe| {
+            recipe
+                .products
+                .iter()
+                .any(|product| product.item == descriptor)
+        })
+    }
+
+    /// Display name of an item, recipe or building, if it is in the catalog.
+    pub fn display_name(&self, path: &str) -> Option<&str> {
+        let name = self
+            .item(path)
+            .map(|item| &item.display_name)
+            .or_else(|| self.recipe(path).map(|recipe| &recipe.display_name))
+            .or_else(|| self.building(path).map(|building| &building.display_name))?;
+
+        Some(name.as_str())
+    }
+
+    /// Describes an amount of something for people, such as `Steel Beam ×2`. Paths missing from
+    /// the catalog are described by their class name.
+    pub fn describe(&self, path: &str, amount: f64) -> String {
+        let name = self.display_name(path).unwrap_or_else(|| class_name(path));
+        format!("{name} ×{amount}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCS: &str = include_str!("../Docs Sample.json");
+
+    #[test]
+    fn check_item_amounts() {
+        let amounts = item_amounts
+            .parse("((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/A/Desc_A.Desc_A_C'\",Amount=2),(ItemClass=BlueprintGeneratedClass'\"/Game/B/Desc_B.Desc_B_C\"',Amount=0.5))")
+            .expect("Parse should succeed");
+        assert_eq!(
+            amounts,
+            [
+                (
+                    "/Game/A/Desc_A.Desc_A_C",
+                    ItemAmount {
+                        item: "Desc_A_C".to_owned(),
+                        amount: 2.0
+                    }
+                ),
+                (
+                    "/Game/B/Desc_B.Desc_B_C",
+                    ItemAmount {
+                        item: "Desc_B_C".to_owned(),
+                        amount: 0.5
+                    }
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn check_catalog() {
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+
+        let steel =
+            "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0";
+        assert_eq!(catalog.display_name(steel), Some("Steel Beam"));
+        assert_eq!(catalog.describe(steel, 2.0), "Steel Beam ×2");
+        assert_eq!(catalog.item_path("Desc_SteelPlate_C"), Some(steel));
+        assert_eq!(
+            catalog.describe("/Game/Unknown.Desc_Unknown_C", 1.0),
+            "Desc_Unknown_C ×1"
+        );
+
+        let recipe = catalog
+            .recipe(
+                "/Game/FactoryGame/Recipes/Buildings/Recipe_GeneratorCoal.Recipe_GeneratorCoal_C\0",
+            )
+            .expect("Recipe should be in the catalog");
+        assert_eq!(recipe.ingredients.len(), 3);
+        assert_eq!(recipe.produced_in, ["BP_BuildGun_C", "FGBuildGun"]);
+
+        let generator = "/Game/FactoryGame/Buildable/Factory/GeneratorCoal/Build_GeneratorCoal.Build_GeneratorCoal_C\0";
+        assert_eq!(
+            catalog.building(generator).and_then(|b| b.power_production),
+            Some(75.0)
+        );
+        assert_eq!(catalog.building_recipe(generator), Some(recipe));
+        let fuels = catalog
+            .building(generator)
+            .map(|b| b.fuels.as_slice())
+            .unwrap_or_default();
+        assert_eq!(fuels.len(), 2);
+        assert_eq!(fuels[0].item, "Desc_Coal_C");
+        assert_eq!(fuels[0].supplemental.as_deref(), Some("Desc_Water_C"));
+        assert_eq!(fuels[0].byproduct, None);
+        assert_eq!(
+            catalog.item("Desc_Coal_C").and_then(|i| i.energy_value),
+            Some(300.0)
+        );
+        assert_eq!(
+            catalog
+                .building("Build_Foundation_8x4_01_C")
+                .and_then(|b| b.footprint),
+            Some([800.0, 800.0, 400.0])
+        );
+
+        // The game writes the file as UTF-16
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(DOCS.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(
+            Catalog::from_bytes(&utf16).expect("Catalog should load"),
+            catalog
+        );
+    }
+}
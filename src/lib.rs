@@ -0,0 +1,6 @@
+pub mod bom;
+pub mod bp_write;
+pub mod catalog;
+pub mod patterns;
+pub mod production;
+pub mod transform;
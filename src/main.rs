@@ -1,13 +1,64 @@
-use crate::bp_write::BPWrite;
-use crate::patterns::Blueprint;
+use color_eyre::eyre::bail;
+use satisfactory_bp::bom::BillOfMaterials;
+use satisfactory_bp::bp_write::BPWrite;
+use satisfactory_bp::catalog::Catalog;
+use satisfactory_bp::patterns::Blueprint;
 use std::io::BufWriter;
 use std::{fs::File, io::Read};
 
-mod bp_write;
-mod patterns;
+/// `bom <blueprint.sbp> <Docs.json> [--csv]` prints what a blueprint costs to place.
+fn bom(args: &[String]) -> color_eyre::Result<()> {
+    let (blueprint_path, docs_path, csv) = match args {
+        [blueprint, docs] => (blueprint, docs, false),
+        [blueprint, docs, flag] if flag == "--csv" => (blueprint, docs, true),
+        _ => bail!("Usage: bom <blueprint.sbp> <Docs.json> [--csv]"),
+    };
+
+    let data = std::fs::read(blueprint_path)?;
+    let blueprint = Blueprint::new(data.as_slice())?;
+    let catalog = Catalog::load(docs_path)?;
+    let bom = BillOfMaterials::new(&blueprint, &catalog);
+
+    if csv {
+        print!("{}", bom.to_csv(&catalog));
+        return Ok(());
+    }
+
+    println!("Buildings:");
+    for building in &bom.buildings {
+        println!(
+            "  {}",
+            catalog.describe(&building.type_path, building.count as f64)
+        );
+    }
+    println!("Cost:");
+    for item in &bom.items {
+        println!("  {}", catalog.describe(&item.item, item.amount));
+    }
+    for cost in bom.recipes.iter().filter(|cost| cost.items.is_none()) {
+        println!(
+            "Recipe missing from the catalog: {}",
+            cost.recipe.trim_end_matches('\0')
+        );
+    }
+    for mismatch in &bom.mismatches {
+        println!(
+            "Resource list has {} but the buildings cost {}",
+            catalog.describe(&mismatch.item, mismatch.stored.into()),
+            mismatch.computed
+        );
+    }
+
+    Ok(())
+}
 
 fn main() -> color_eyre::Result<()> {
-    // let blueprint = Blueprint::default();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "bom") {
+        return bom(&args[1..]);
+    }
+
+    // let blueprint = Blueprint::builder().build();
     // let file = File::open("./empty.sbp")?;
     // let mut writer = BufWriter::new(file);
     // blueprint.bp_write(&mut writer)?;
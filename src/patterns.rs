@@ -1,11 +1,16 @@
 pub mod body;
+pub mod builder;
 pub mod factory_string;
 pub mod header;
 
 use crate::bp_write::BPWrite;
+use crate::catalog::Catalog;
+use crate::transform::{BoundingBox, Quat, Transform, Vec3};
 use body::*;
+use color_eyre::eyre::bail;
 use flate2::{Compression, read, write};
 use header::*;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Read, Seek, SeekFrom, Write};
 use winnow::stream::AsBytes;
 use winnow::{Bytes, Parser};
@@ -34,6 +39,184 @@ impl<'header> Blueprint<'header> {
 
         Ok(Blueprint { header, body })
     }
+
+    /// Rotates and translates the whole blueprint about its grid centre.
+    /// The rotation is snapped to quarter turns around the vertical axis and the translation to
+    /// whole foundations, so that buildings stay on the grid described by
+    /// [`Header::blueprint_size`]. The grid is [`FOUNDATION_SIZE`] high as well, so the height
+    /// also moves in steps of a whole foundation. Returns the transform that was actually
+    /// applied. Transforms with a pitch, roll or scale cannot keep the blueprint on the grid and
+    /// are rejected. Use [`BlueprintBody::transform`] to move the buildings off the grid.
+    pub fn transform(&mut self, transform: &Transform) -> color_eyre::Result<Transform> {
+        let rotator = transform.rotation.to_rotator();
+        if rotator.pitch.abs() > 1e-3 || rotator.roll.abs() > 1e-3 {
+            bail!(
+                "Blueprints can only turn around the vertical axis, not by a pitch of {} and a roll of {}",
+                rotator.pitch,
+                rotator.roll
+            );
+        }
+        if !transform.scale.abs_diff_eq(Vec3::ONE, 1e-6) {
+            bail!("Blueprints cannot be scaled, got {:?}", transform.scale);
+        }
+
+        let quarter_turns = (rotator.yaw / 90.0).round();
+        let snap = |value: f64| (value / FOUNDATION_SIZE).round() * FOUNDATION_SIZE;
+        let translation = transform.translation;
+
+        let snapped = Transform::new(
+            Quat::from_yaw(quarter_turns * 90.0),
+            Vec3::new(
+                snap(translation.x),
+                snap(translation.y),
+                snap(translation.z),
+            ),
+            Vec3::ONE,
+        );
+
+        // A quarter turn swaps the width and length of the blueprint
+        if quarter_turns as i64 % 2 != 0 {
+            self.header.blueprint_size.swap(0, 1);
+        }
+        self.body.transform(&snapped);
+
+        Ok(snapped)
+    }
+
+    /// Adds the buildings of `other` to this blueprint, moved by `offset` which is snapped to the
+    /// grid as in [`Blueprint::transform`]. Colliding instance names in `other` are renamed along
+    /// with every reference to them, and the renames are returned. The blueprint grows to fit the
+    /// moved blueprint and the resource and recipe lists of both blueprints are combined.
+    /// Fails without changing this blueprint if `offset` is rejected by [`Blueprint::transform`].
+    pub fn merge(
+        &mut self,
+        mut other: Blueprint<'header>,
+        offset: &Transform,
+    ) -> color_eyre::Result<HashMap<String, String>> {
+        let applied = other.transform(offset)?;
+        let foundations = |value: f64| (value / FOUNDATION_SIZE).round().abs() as u32;
+        let translation = applied.translation;
+
+        let [width, length, height] = &mut self.header.blueprint_size;
+        let [other_width, other_length, other_height] = other.header.blueprint_size;
+        // Both blueprints are centred on the origin so offsets count on both sides
+        *width = (*width).max(other_width + 2 * foundations(translation.x));
+        *length = (*length).max(other_length + 2 * foundations(translation.y));
+        *height = (*height).max(other_height + foundations(translation.z));
+
+        for resource in other.header.resource_list.resources {
+            let resources = &mut self.header.resource_list.resources;
+            match resources.iter_mut().find(|r| r.path == resource.path) {
+                Some(existing) => existing.count += resource.count,
+                None => resources.push(resource),
+            }
+        }
+
+        for recipe in other.header.recipie_list.recipies {
+            if !self.header.recipie_list.recipies.contains(&recipe) {
+                self.header.recipie_list.recipies.push(recipe);
+            }
+        }
+
+        Ok(self.body.merge(other.body))
+    }
+
+    /// Extracts the actors matching `predicate` into a new blueprint, see [`BlueprintBody::split`].
+    /// The blueprint size shrinks to fit the extracted actors while staying on the same grid, and
+    /// the recipe list keeps the recipes they were built with. The resource list is recomputed
+    /// from the recipes in `catalog`, see [`Blueprint::recompute_resources`].
+    pub fn split(
+        &self,
+        predicate: impl Fn(&ActorHeader) -> bool,
+        catalog: &'header Catalog,
+    ) -> (Blueprint<'header>, Vec<DanglingReference>) {
+        let (body, references) = self.body.split(predicate);
+        let mut header = self.header.clone();
+
+        // Foundations needed to keep a building centred at `value` inside a grid of `size`. Odd
+        // sizes put a foundation on the centre and even ones a grid line, so the parity is kept.
+        let fit = |value: f64, size: u32| {
+            let needed = ((value.abs() + 1.0) / (FOUNDATION_SIZE / 2.0)).floor() as u32 + 1;
+            let needed = needed + (needed + size) % 2;
+
+            needed.min(size.max(1))
+        };
+        let [width, length, height] = self.header.blueprint_size;
+        let mut blueprint_size = [fit(0.0, width), fit(0.0, length), 1];
+        for actor in body.actor_headers() {
+            let position = actor.position();
+            let floors = (position.z.max(0.0) / FOUNDATION_SIZE).floor() as u32 + 1;
+
+            blueprint_size[0] = blueprint_size[0].max(fit(position.x, width));
+            blueprint_size[1] = blueprint_size[1].max(fit(position.y, length));
+            blueprint_size[2] = blueprint_size[2].max(floors.min(height.max(1)));
+        }
+        header.blueprint_size = blueprint_size;
+
+        let recipes: Vec<&str> = body
+            .objects
+            .iter()
+            .filter_map(|object| object.properties().find("mBuiltWithRecipe"))
+            .filter_map(|prop| match &prop.property {
+                PropertyType::ObjectProperty(recipe) => Some(recipe.reference.as_str()),
+                _ => None,
+            })
+            .collect();
+        header
+            .recipie_list
+            .recipies
+            .retain(|recipe| recipes.contains(&recipe.0));
+
+        let mut blueprint = Blueprint { header, body };
+        blueprint.recompute_resources(catalog);
+        (blueprint, references)
+    }
+
+    /// Extracts the actors positioned inside `bounds`, see [`Blueprint::split`].
+    pub fn split_box(
+        &self,
+        bounds: &BoundingBox,
+        catalog: &'header Catalog,
+    ) -> (Blueprint<'header>, Vec<DanglingReference>) {
+        self.split(|actor| bounds.contains(actor.position()), catalog)
+    }
+
+    /// Replaces building types and recipes using `swap`, see [`BlueprintBody::swap_recipes`].
+    /// The recipe list follows and the resource list is recomputed from the recipes in `catalog`,
+    /// see [`Blueprint::recompute_resources`]. Returns the renamed instance names.
+    pub fn swap_recipes(
+        &mut self,
+        swap: &RecipeSwap<'header>,
+        catalog: &'header Catalog,
+    ) -> HashMap<String, String> {
+        self.swap_recipe_list(swap);
+        let renames = self.body.swap_recipes(swap);
+        self.recompute_resources(catalog);
+
+        renames
+    }
+
+    /// Mirrors the blueprint, see [`BlueprintBody::mirror`]. The recipe list follows the handed
+    /// buildings that were swapped. Returns the renamed instance names.
+    pub fn mirror(
+        &mut self,
+        axis: MirrorAxis,
+        table: &MirrorTable<'header>,
+    ) -> HashMap<String, String> {
+        self.swap_recipe_list(table.swap());
+        self.body.mirror(axis, table)
+    }
+
+    fn swap_recipe_list(&mut self, swap: &RecipeSwap<'header>) {
+        let recipes = &mut self.header.recipie_list.recipies;
+        for recipe in recipes.iter_mut() {
+            if let Some(new_recipe) = swap.recipe(recipe.0) {
+                recipe.0 = new_recipe;
+            }
+        }
+        let mut seen = HashSet::new();
+        recipes.retain(|recipe| seen.insert(recipe.0));
+    }
 }
 
 impl<W: Write + Seek> BPWrite<W> for Blueprint<'_> {
@@ -78,7 +261,7 @@ mod tests {
     use std::io::Cursor;
 
     use super::*;
-    use crate::bp_write::BPWrite;
+    use crate::{bp_write::BPWrite, transform::Rotator};
     use winnow::{Bytes, Parser};
 
     #[test]
@@ -87,11 +270,11 @@ mod tests {
 
         let data: &mut &Bytes = &mut Bytes::new(DATA);
 
-        let header = header
+        let _header = header
             .parse_next(data)
             .expect("header parse should succeed");
 
-        let rest: &[u8] = data;
+        let _rest: &[u8] = data;
 
         // let mut compressed_body = Vec::with_capacity(header.body_header.uncompressed_size as usize);
         // let mut decoder = read::ZlibDecoder::new(rest);
@@ -121,4 +304,323 @@ mod tests {
 
         assert_eq!(&buf.get_ref()[..471], &DATA[..471]);
     }
+
+    #[test]
+    fn check_coal_body() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+
+        let data: &mut &Bytes = &mut Bytes::new(DATA);
+        let _header = header
+            .parse_next(data)
+            .expect("header parse should succeed");
+
+        let mut body_bytes = Vec::new();
+        read::ZlibDecoder::new(data.as_bytes())
+            .read_to_end(&mut body_bytes)
+            .expect("body should decompress");
+
+        let body = blueprint_body
+            .parse(body_bytes.as_slice().into())
+            .expect("body parse should succeed");
+        assert_eq!(body.object_headers.len(), 97);
+        assert_eq!(body.objects.len(), 97);
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("body write should succeed");
+        assert_eq!(buf, body_bytes);
+    }
+
+    #[test]
+    fn check_transform() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let original = blueprint.body.clone();
+        let [width, length, height] = blueprint.header.blueprint_size;
+
+        let applied = blueprint
+            .transform(&Transform::new(
+                Quat::from_yaw(80.0),
+                Vec3::new(850.0, -790.0, 30.0),
+                Vec3::ONE,
+            ))
+            .expect("Transform should succeed");
+        assert!(applied.abs_diff_eq(
+            &Transform::new(
+                Quat::from_yaw(90.0),
+                Vec3::new(800.0, -800.0, 0.0),
+                Vec3::ONE
+            ),
+            1e-9
+        ));
+        assert_eq!(blueprint.header.blueprint_size, [length, width, height]);
+
+        let ObjectHeaderType::Actor(generator) = &blueprint.body.object_headers[0] else {
+            panic!("Expected an actor header");
+        };
+        assert!(
+            generator
+                .position()
+                .abs_diff_eq(Vec3::new(1000.0, -1800.0, 0.0), 1e-3)
+        );
+
+        // Undoing the transform restores the original actors
+        blueprint.body.transform(&applied.inverse());
+        for (header, original) in blueprint
+            .body
+            .object_headers
+            .iter()
+            .zip(&original.object_headers)
+        {
+            if let (ObjectHeaderType::Actor(actor), ObjectHeaderType::Actor(original)) =
+                (header, original)
+            {
+                assert!(actor.transform().abs_diff_eq(&original.transform(), 1e-2));
+            }
+        }
+
+        // Tilted or scaled transforms are rejected rather than flattened
+        let before = blueprint.body.clone();
+        let tilted = Quat::from_rotator(Rotator {
+            yaw: 90.0,
+            pitch: 10.0,
+            roll: 0.0,
+        });
+        assert!(
+            blueprint
+                .transform(&Transform::from_rotation(tilted))
+                .is_err()
+        );
+        assert!(
+            blueprint
+                .transform(&Transform::new(
+                    Quat::IDENTITY,
+                    Vec3::ZERO,
+                    Vec3::new(2.0, 2.0, 2.0)
+                ))
+                .is_err()
+        );
+        assert_eq!(blueprint.body, before);
+    }
+
+    #[test]
+    fn check_merge() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let other = Blueprint::new(DATA).expect("Parse should succeed");
+        let original = blueprint.header.clone();
+
+        let renames = blueprint
+            .merge(
+                other,
+                &Transform::from_translation(Vec3::new(0.0, 4000.0, 0.0)),
+            )
+            .expect("Merge should succeed");
+        assert_eq!(renames.len(), 25);
+        assert_eq!(blueprint.body.object_headers.len(), 194);
+        assert_eq!(blueprint.body.objects.len(), 194);
+
+        let [width, length, height] = original.blueprint_size;
+        assert_eq!(
+            blueprint.header.blueprint_size,
+            [width, length + 10, height]
+        );
+        for (merged, resource) in blueprint
+            .header
+            .resource_list
+            .resources
+            .iter()
+            .zip(&original.resource_list.resources)
+        {
+            assert_eq!(merged.count, resource.count * 2);
+        }
+        assert_eq!(blueprint.header.recipie_list, original.recipie_list);
+
+        // The copy only references its own actors
+        let mut copy = BlueprintBody {
+            object_headers: blueprint.body.object_headers[97..].to_vec(),
+            objects: blueprint.body.objects[97..].to_vec(),
+        };
+        copy.for_each_path_name_mut(&mut |path_name| {
+            assert!(!renames.contains_key(path_name.as_str()));
+        });
+        for header in &copy.object_headers {
+            if let ObjectHeaderType::Component(component) = header {
+                assert!(renames.values().any(|name| *name == component.parent_actor));
+            }
+        }
+
+        // Both ends of every copied wire point at the renamed power connections
+        let components: Vec<&str> = copy
+            .object_headers
+            .iter()
+            .filter_map(|header| match header {
+                ObjectHeaderType::Component(component) => Some(component.instance_name.as_str()),
+                ObjectHeaderType::Actor(_) => None,
+            })
+            .collect();
+        let mut wires = 0;
+        for object in &copy.objects {
+            if let ObjectType::Actor(ActorObject {
+                extra_data: ExtraData::Wire { source, target },
+                ..
+            }) = object
+            {
+                assert!(components.contains(&source.path_name.as_str()));
+                assert!(components.contains(&target.path_name.as_str()));
+                wires += 1;
+            }
+        }
+        assert_eq!(wires, 3);
+
+        let mut buf = Vec::new();
+        blueprint
+            .body
+            .bp_write(&mut buf)
+            .expect("body write should succeed");
+        let body = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("body parse should succeed");
+        assert_eq!(body, blueprint.body);
+    }
+
+    #[test]
+    fn check_split() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+        const DOCS: &str = include_str!("../Docs Sample.json");
+
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+        let (generators, references) = blueprint.split(
+            |actor| actor.type_path.contains("Build_GeneratorCoal_C"),
+            &catalog,
+        );
+
+        // Each generator keeps its seven components
+        assert_eq!(generators.body.actor_headers().count(), 3);
+        assert_eq!(generators.body.object_headers.len(), 24);
+        assert_eq!(generators.body.objects.len(), 24);
+        assert_eq!(blueprint.header.blueprint_size, [4, 4, 4]);
+        assert_eq!(generators.header.blueprint_size, [4, 2, 1]);
+        assert_eq!(
+            generators.header.recipie_list.recipies,
+            [Recipe(
+                "/Game/FactoryGame/Recipes/Buildings/Recipe_GeneratorCoal.Recipe_GeneratorCoal_C\0"
+            )]
+        );
+
+        // The split only costs its three generators
+        assert_eq!(
+            generators.header.resource_list.resources,
+            [
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/Cable/Desc_Cable.Desc_Cable_C\0",
+                    count: 90
+                },
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/Rotor/Desc_Rotor.Desc_Rotor_C\0",
+                    count: 30
+                },
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0",
+                    count: 60
+                }
+            ]
+        );
+
+        // Connections to the belts, pipes and wires that were left out are pruned
+        assert!(!references.is_empty());
+        assert!(references.iter().all(|reference| reference.pruned));
+        let names: Vec<String> = generators
+            .body
+            .object_headers
+            .iter()
+            .map(|header| header.instance_name().trim_end_matches('\0').to_owned())
+            .collect();
+        let mut body = generators.body.clone();
+        body.for_each_path_name_mut(&mut |path_name| {
+            if path_name.starts_with("Persistent_Level:PersistentLevel.Build_") {
+                assert!(
+                    names
+                        .iter()
+                        .any(|name| path_name.starts_with(name.as_str()))
+                );
+            }
+        });
+
+        let (bounded, _) = blueprint.split_box(
+            &BoundingBox::new(
+                Vec3::new(-1500.0, -500.0, -100.0),
+                Vec3::new(500.0, 100.0, 100.0),
+            ),
+            &catalog,
+        );
+        assert_eq!(bounded.body.actor_headers().count(), 2);
+    }
+
+    #[test]
+    fn check_swap_recipes() {
+        const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+        const DOCS: &str = include_str!("../Docs Sample.json");
+        const MK3: &str =
+            "/Game/FactoryGame/Recipes/Buildings/Recipe_ConveyorBeltMk3.Recipe_ConveyorBeltMk3_C\0";
+        const LIFT: &str =
+            "/Game/FactoryGame/Recipes/Buildings/Recipe_ConveyorLiftMk1.Recipe_ConveyorLiftMk1_C\0";
+
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+        let recipes = blueprint.header.recipie_list.recipies.len();
+        assert!(
+            blueprint
+                .header
+                .recipie_list
+                .recipies
+                .contains(&Recipe(MK3))
+        );
+        assert!(
+            blueprint
+                .header
+                .recipie_list
+                .recipies
+                .contains(&Recipe(LIFT))
+        );
+
+        // Two recipes merged into one leave a single entry in the list
+        let renames = blueprint.swap_recipes(&RecipeSwap::new().with_recipe(MK3, LIFT), &catalog);
+        assert!(renames.is_empty());
+        assert_eq!(blueprint.header.recipie_list.recipies.len(), recipes - 1);
+        assert!(
+            !blueprint
+                .header
+                .recipie_list
+                .recipies
+                .contains(&Recipe(MK3))
+        );
+        assert_eq!(
+            blueprint
+                .body
+                .query()
+                .reference("mBuiltWithRecipe", MK3)
+                .iter()
+                .count(),
+            0
+        );
+
+        // The resources are recomputed, and the sample catalog only prices the generators
+        assert_eq!(
+            blueprint
+                .header
+                .resource_list
+                .resources
+                .iter()
+                .map(|resource| (class_name(resource.path), resource.count))
+                .collect::<Vec<_>>(),
+            [
+                ("Desc_Cable_C", 90),
+                ("Desc_Rotor_C", 30),
+                ("Desc_SteelPlate_C", 60)
+            ]
+        );
+    }
 }
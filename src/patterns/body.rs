@@ -1,42 +1,290 @@
+mod connection_graph;
+mod customization;
+mod entity;
+mod instance_name;
+mod lighting;
+mod mirror;
 mod object;
 mod object_header;
 mod object_ref;
+mod pipe;
+mod power;
 mod property_list;
+mod property_path;
+mod query;
+mod railway;
+mod recipe_swap;
+mod repaint;
+mod sign;
+mod spline;
+mod splitter;
 
 use crate::bp_write::BPWrite;
+use crate::transform::Transform;
+pub use connection_graph::*;
+pub use customization::*;
+pub use entity::*;
+pub use instance_name::*;
+pub use lighting::*;
+pub use mirror::*;
 pub use object::*;
 pub use object_header::*;
 pub use object_ref::*;
+pub use pipe::*;
+pub use power::*;
 pub use property_list::*;
+pub use property_path::*;
+pub use query::*;
+pub use railway::*;
+pub use recipe_swap::*;
+pub use repaint::*;
+pub use sign::*;
+pub use spline::*;
+pub use splitter::*;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Write};
 use winnow::{
     Bytes, Parser,
     binary::le_u32,
-    combinator::{repeat, seq},
+    combinator::{preceded, repeat},
     error::StrContext,
 };
 
+/// Vector properties holding world space positions rather than positions relative to their actor
+const WORLD_POSITIONS: &[&str] = &["Locations\0"];
+
+/// Reference to an actor, or one of its components, that is not part of the body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    /// Instance name of the object holding the reference
+    pub object: String,
+    pub reference: String,
+    /// Whether the reference was removed, which is only possible for object properties
+    pub pruned: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct BlueprintBody {
     pub object_headers: Vec<ObjectHeaderType>,
     pub objects: Vec<ObjectType>,
 }
 
+impl BlueprintBody {
+    pub fn actor_headers(&self) -> impl Iterator<Item = &ActorHeader> {
+        self.object_headers
+            .iter()
+            .filter_map(|header| match header {
+                ObjectHeaderType::Actor(actor) => Some(actor),
+                ObjectHeaderType::Component(_) => None,
+            })
+    }
+
+    pub fn actor_headers_mut(&mut self) -> impl Iterator<Item = &mut ActorHeader> {
+        self.object_headers
+            .iter_mut()
+            .filter_map(|header| match header {
+                ObjectHeaderType::Actor(actor) => Some(actor),
+                ObjectHeaderType::Component(_) => None,
+            })
+    }
+
+    /// Calls `f` with every instance name and reference to an object, in headers and objects.
+    pub fn for_each_path_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        for header in &mut self.object_headers {
+            header.for_each_path_name_mut(f);
+        }
+
+        for object in &mut self.objects {
+            object.for_each_path_name_mut(f);
+        }
+    }
+
+    /// Renames actors using `renames`, which maps old instance names to new ones. The components of
+    /// a renamed actor and every reference to the actor or its components are renamed with it.
+    pub fn rename_actors(&mut self, renames: &HashMap<String, String>) {
+        if renames.is_empty() {
+            return;
+        }
+
+        self.for_each_path_name_mut(&mut |path_name| {
+            if let Some(new_name) = renames.get(path_name.as_str()) {
+                *path_name = new_name.clone();
+                return;
+            }
+
+            // Components are named after their actor, e.g. `<actor>.PowerConnection`
+            let Some((actor, component)) = path_name.rsplit_once('.') else {
+                return;
+            };
+            if let Some(new_name) = renames.get(&format!("{actor}\0")) {
+                *path_name = format!("{}.{component}", new_name.trim_end_matches('\0'));
+            }
+        });
+    }
+
+    /// Moves every object of `other` into this body. Actors of `other` whose instance name is
+    /// already used here are renamed, together with everything referencing them.
+    /// Returns the renamed instance names, mapping the old names to the new ones.
+    pub fn merge(&mut self, mut other: BlueprintBody) -> HashMap<String, String> {
+        let mut names = self.instance_names();
+        let mut renames = HashMap::new();
+        for actor in other.actor_headers() {
+            if names.contains(&actor.instance_name) {
+                renames.insert(actor.instance_name.clone(), String::new());
+            } else {
+                names.reserve(&actor.instance_name);
+            }
+        }
+        for actor in other.actor_headers() {
+            if let Some(new_name) = renames.get_mut(&actor.instance_name) {
+                *new_name = names.allocate(&actor.type_path);
+            }
+        }
+
+        other.rename_actors(&renames);
+        self.object_headers.append(&mut other.object_headers);
+        self.objects.append(&mut other.objects);
+
+        renames
+    }
+
+    /// Copies the actors matching `predicate` and their components into a new body.
+    /// Object properties referencing actors that were left out, or their components, are removed
+    /// and such entries of object arrays are cleared. Other references to them, such as the
+    /// endpoints of wires, are kept. All of them are returned.
+    pub fn split(
+        &self,
+        predicate: impl Fn(&ActorHeader) -> bool,
+    ) -> (BlueprintBody, Vec<DanglingReference>) {
+        let left_out: HashSet<&str> = self
+            .actor_headers()
+            .filter(|actor| !predicate(actor))
+            .map(|actor| actor.instance_name.as_str())
+            .collect();
+        let is_left_out = |name: &str| left_out.contains(name);
+        let dangling = |path_name: &str| {
+            is_left_out(path_name)
+                || path_name
+                    .rsplit_once('.')
+                    .is_some_and(|(actor, _)| is_left_out(&format!("{actor}\0")))
+        };
+
+        let mut body = BlueprintBody::default();
+        let mut references = Vec::new();
+        for (header, object) in self.object_headers.iter().zip(&self.objects) {
+            let selected = match header {
+                ObjectHeaderType::Actor(actor) => !is_left_out(&actor.instance_name),
+                ObjectHeaderType::Component(component) => !is_left_out(&component.parent_actor),
+            };
+            if !selected {
+                continue;
+            }
+
+            let mut object = object.clone();
+            let object_name = header.instance_name();
+            references.extend(
+                object
+                    .properties_mut()
+                    .prune_references(&dangling)
+                    .into_iter()
+                    .map(|reference| DanglingReference {
+                        object: object_name.to_owned(),
+                        reference,
+                        pruned: true,
+                    }),
+            );
+            object.for_each_path_name_mut(&mut |path_name| {
+                if dangling(path_name) {
+                    references.push(DanglingReference {
+                        object: object_name.to_owned(),
+                        reference: path_name.clone(),
+                        pruned: false,
+                    });
+                }
+            });
+
+            body.object_headers.push(header.clone());
+            body.objects.push(object);
+        }
+
+        (body, references)
+    }
+
+    /// Applies `transform` to every actor, without any snapping.
+    /// Spline points and other actor relative vectors move with their actor, while the world space
+    /// vectors of wires are transformed directly.
+    pub fn transform(&mut self, transform: &Transform) {
+        for header in &mut self.object_headers {
+            if let ObjectHeaderType::Actor(actor) = header {
+                actor.set_transform(&(*transform * actor.transform()));
+            }
+        }
+
+        for object in &mut self.objects {
+            object
+                .properties_mut()
+                .for_each_struct_mut(&mut |name, data| {
+                    if let StructDataType::Vector(vector) = data
+                        && WORLD_POSITIONS.contains(&name)
+                    {
+                        *vector = transform.transform_point(*vector);
+                    }
+                });
+        }
+    }
+}
+
+/// Checks that every component comes after the actor it belongs to.
+fn components_follow_actors(object_headers: &[ObjectHeaderType]) -> bool {
+    let mut actors = HashSet::new();
+
+    object_headers.iter().all(|header| match header {
+        ObjectHeaderType::Actor(actor) => {
+            actors.insert(actor.instance_name.as_str());
+            true
+        }
+        ObjectHeaderType::Component(component) => actors.contains(component.parent_actor.as_str()),
+    })
+}
+
 pub fn blueprint_body(data: &mut &Bytes) -> winnow::Result<BlueprintBody> {
-    seq! {BlueprintBody {
-        _: le_u32.context(StrContext::Label("body size")),
-        _: le_u32.context(StrContext::Label("object headers size")),
-        object_headers: le_u32.context(StrContext::Label("object headers count"))
-            .flat_map(
-                |count| repeat(count as usize, object_header_type)
-            ).context(StrContext::Label("object headers")),
-        _: le_u32.context(StrContext::Label("objects size")),
-        objects: le_u32.context(StrContext::Label("object headers count"))
-            .flat_map(
-                |count| repeat(count as usize, actor_object.map(ObjectType::Actor))
-            ).context(StrContext::Label("objects")),
-    }}
-    .parse_next(data)
+    let object_headers: Vec<ObjectHeaderType> = preceded(
+        (
+            le_u32.context(StrContext::Label("body size")),
+            le_u32.context(StrContext::Label("object headers size")),
+        ),
+        le_u32
+            .context(StrContext::Label("object headers count"))
+            .flat_map(|count| repeat(count as usize, object_header_type)),
+    )
+    .verify(|headers: &Vec<ObjectHeaderType>| components_follow_actors(headers))
+    .context(StrContext::Label(
+        "object headers with components after their actor",
+    ))
+    .parse_next(data)?;
+
+    preceded(
+        le_u32.context(StrContext::Label("objects size")),
+        le_u32.verify(|count| *count as usize == object_headers.len()),
+    )
+    .context(StrContext::Label("objects count matching the headers"))
+    .parse_next(data)?;
+
+    // Objects are stored in the same order as their headers which decide how they are parsed
+    let objects = object_headers
+        .iter()
+        .map(|header| match header {
+            ObjectHeaderType::Actor(_) => actor_object.map(ObjectType::Actor).parse_next(data),
+            ObjectHeaderType::Component(_) => {
+                component_object.map(ObjectType::Component).parse_next(data)
+            }
+        })
+        .collect::<winnow::Result<Vec<_>>>()?;
+
+    Ok(BlueprintBody {
+        object_headers,
+        objects,
+    })
 }
 
 impl<W: Write> BPWrite<W> for &BlueprintBody {
@@ -45,7 +293,7 @@ impl<W: Write> BPWrite<W> for &BlueprintBody {
         let headers_size: u32 = self
             .object_headers
             .iter()
-            .map(|h| dbg!(h.size()))
+            .map(ObjectHeaderType::size)
             .sum::<u32>()
             + 4;
         let headers_count: u32 = self
@@ -94,4 +342,33 @@ mod tests {
         body.bp_write(&mut buf).expect("write should succeed");
         assert_eq!(buf, DATA);
     }
+
+    #[test]
+    fn check_invalid_body() {
+        const DATA: &[u8] = include_bytes!("../../Coal Left to Right.sbp");
+
+        let body = crate::patterns::Blueprint::new(DATA)
+            .expect("Parse should succeed")
+            .body;
+
+        // Every header needs an object
+        let mut missing_object = body.clone();
+        missing_object.objects.pop();
+        let mut buf = Vec::new();
+        missing_object
+            .bp_write(&mut buf)
+            .expect("write should succeed");
+        assert!(blueprint_body.parse(buf.as_slice().into()).is_err());
+
+        // Components cannot come before their actor
+        let mut out_of_order = body.clone();
+        let last = out_of_order.object_headers.len() - 1;
+        out_of_order.object_headers.swap(0, last);
+        out_of_order.objects.swap(0, last);
+        let mut buf = Vec::new();
+        out_of_order
+            .bp_write(&mut buf)
+            .expect("write should succeed");
+        assert!(blueprint_body.parse(buf.as_slice().into()).is_err());
+    }
 }
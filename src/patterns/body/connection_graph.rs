@@ -0,0 +1,340 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use crate::patterns::body::{
+    ArrayValue, BlueprintBody, ExtraData, ObjectHeaderType, ObjectType, PropertyList,
+};
+
+/// Component a belt, pipe or track connection is attached to
+pub const CONNECTED_COMPONENT: &str = "mConnectedComponent";
+/// Components a railway track connection is attached to, more than one at a switch
+pub const CONNECTED_COMPONENTS: &str = "mConnectedComponents";
+
+/// What a connection carries, found from the class of the components it joins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionKind {
+    Belt,
+    Pipe,
+    Power,
+    Rail,
+}
+
+impl ConnectionKind {
+    /// Kind of the connection component class `class_name`, if it is one.
+    pub fn from_class(class_name: &str) -> Option<Self> {
+        let class_name = class_name.trim_end_matches('\0');
+        let class = class_name
+            .rsplit_once('.')
+            .map_or(class_name, |(_, class)| class);
+
+        match class {
+            "FGFactoryConnectionComponent" => Some(ConnectionKind::Belt),
+            "FGPipeConnectionComponent" | "FGPipeConnectionFactory" => Some(ConnectionKind::Pipe),
+            "FGPowerConnectionComponent" => Some(ConnectionKind::Power),
+            "FGRailroadTrackConnectionComponent" => Some(ConnectionKind::Rail),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ConnectionKind::Belt => "belt",
+            ConnectionKind::Pipe => "pipe",
+            ConnectionKind::Power => "power",
+            ConnectionKind::Rail => "rail",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            ConnectionKind::Belt => "orange",
+            ConnectionKind::Pipe => "blue",
+            ConnectionKind::Power => "gold",
+            ConnectionKind::Rail => "gray",
+        }
+    }
+}
+
+/// A link between the components of two buildings, indices are into [`ConnectionGraph::nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub kind: ConnectionKind,
+    pub from: usize,
+    pub to: usize,
+    pub from_component: String,
+    pub to_component: String,
+    /// Power line joining the components of a power connection
+    pub wire: Option<String>,
+}
+
+/// A connection to a component that is not in the body, or that does not connect back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingConnection {
+    pub kind: ConnectionKind,
+    /// Instance name of the object holding the connection
+    pub object: String,
+    pub target: String,
+}
+
+/// Buildings of a blueprint and the connections between them, see
+/// [`BlueprintBody::connections`]. Power lines are edges rather than nodes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConnectionGraph {
+    /// Instance names of the buildings
+    pub nodes: Vec<String>,
+    pub edges: Vec<Connection>,
+    pub dangling: Vec<DanglingConnection>,
+}
+
+/// Path names of the components a connection component is attached to.
+pub(crate) fn connected_components(properties: &PropertyList) -> Vec<&str> {
+    let mut targets: Vec<&str> = properties
+        .get::<&str>(CONNECTED_COMPONENT)
+        .into_iter()
+        .collect();
+    if let Some(ArrayValue::Object(refs)) = properties.get::<&ArrayValue>(CONNECTED_COMPONENTS) {
+        targets.extend(refs.iter().map(|object_ref| object_ref.path_name.as_str()));
+    }
+
+    targets.retain(|target| !target.trim_end_matches('\0').is_empty());
+    targets
+}
+
+impl BlueprintBody {
+    /// Builds the graph of belt, pipe, rail and power connections between the buildings.
+    /// Connections are listed once even though both of their components refer to each other.
+    pub fn connections(&self) -> ConnectionGraph {
+        let mut graph = ConnectionGraph::default();
+        let mut nodes = HashMap::new();
+        // Connection components by instance name, with their parent actor and kind
+        let mut components = HashMap::new();
+
+        for entity in self.entities() {
+            match (entity.header, entity.object) {
+                (ObjectHeaderType::Actor(header), ObjectType::Actor(actor))
+                    if !matches!(actor.extra_data, ExtraData::Wire { .. }) =>
+                {
+                    nodes.insert(header.instance_name.as_str(), graph.nodes.len());
+                    graph.nodes.push(header.instance_name.clone());
+                }
+                (ObjectHeaderType::Component(header), ObjectType::Component(component)) => {
+                    if let Some(kind) = ConnectionKind::from_class(&header.class_name) {
+                        components.insert(
+                            header.instance_name.as_str(),
+                            (header.parent_actor.as_str(), kind, &component.properties),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut linked = HashSet::new();
+        let mut link =
+            |graph: &mut ConnectionGraph, kind, (from, to): (&str, &str), wire: Option<&str>| {
+                let (Some(&(from_actor, ..)), Some(&(to_actor, ..))) =
+                    (components.get(from), components.get(to))
+                else {
+                    return;
+                };
+                let (Some(&from_node), Some(&to_node)) =
+                    (nodes.get(from_actor), nodes.get(to_actor))
+                else {
+                    return;
+                };
+                let key = if from < to { (from, to) } else { (to, from) };
+                if wire.is_none() && !linked.insert((key.0.to_owned(), key.1.to_owned())) {
+                    return;
+                }
+
+                graph.edges.push(Connection {
+                    kind,
+                    from: from_node,
+                    to: to_node,
+                    from_component: from.to_owned(),
+                    to_component: to.to_owned(),
+                    wire: wire.map(ToOwned::to_owned),
+                });
+            };
+
+        for entity in self.entities() {
+            match (entity.header, entity.object) {
+                (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) => {
+                    let ExtraData::Wire { source, target } = &actor.extra_data else {
+                        continue;
+                    };
+                    for end in [source, target] {
+                        if !components.contains_key(end.path_name.as_str()) {
+                            graph.dangling.push(DanglingConnection {
+                                kind: ConnectionKind::Power,
+                                object: header.instance_name.clone(),
+                                target: end.path_name.clone(),
+                            });
+                        }
+                    }
+                    link(
+                        &mut graph,
+                        ConnectionKind::Power,
+                        (&source.path_name, &target.path_name),
+                        Some(&header.instance_name),
+                    );
+                }
+                (ObjectHeaderType::Component(header), ObjectType::Component(_)) => {
+                    let Some(&(_, kind, properties)) =
+                        components.get(header.instance_name.as_str())
+                    else {
+                        continue;
+                    };
+                    for target in connected_components(properties) {
+                        let connects_back = components.get(target).is_some_and(|(_, _, other)| {
+                            connected_components(other).contains(&header.instance_name.as_str())
+                        });
+                        if connects_back {
+                            link(&mut graph, kind, (&header.instance_name, target), None);
+                        } else {
+                            graph.dangling.push(DanglingConnection {
+                                kind,
+                                object: header.instance_name.clone(),
+                                target: target.to_owned(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        graph
+    }
+}
+
+impl ConnectionGraph {
+    pub fn node(&self, instance_name: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node == instance_name)
+    }
+
+    /// Connections of a node, with the node at their other end.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, &Connection)> {
+        self.edges.iter().filter_map(move |edge| match node {
+            _ if edge.from == node => Some((edge.to, edge)),
+            _ if edge.to == node => Some((edge.from, edge)),
+            _ => None,
+        })
+    }
+
+    /// Nodes reachable from `start` through connections of the given kinds, in breadth first
+    /// order starting with `start` itself.
+    pub fn reachable(&self, start: usize, kinds: &[ConnectionKind]) -> Vec<usize> {
+        let mut visited = vec![start];
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            for (next, edge) in self.neighbors(node) {
+                if kinds.contains(&edge.kind) && !visited.contains(&next) {
+                    visited.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Writes the graph in the DOT language of GraphViz, with buildings labelled by their
+    /// instance name without the level and edges coloured by kind.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph blueprint {\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let node = node.trim_end_matches('\0');
+            let label = node.rsplit_once('.').map_or(node, |(_, name)| name);
+            let _ = writeln!(
+                dot,
+                "    n{index} [label=\"{}\"];",
+                label.replace('"', "\\\"")
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    n{} -- n{} [label=\"{}\", color={}];",
+                edge.from,
+                edge.to,
+                edge.kind.name(),
+                edge.kind.color()
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    fn count(graph: &ConnectionGraph, kind: ConnectionKind) -> usize {
+        graph.edges.iter().filter(|edge| edge.kind == kind).count()
+    }
+
+    #[test]
+    fn check_connection_graph() {
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let graph = body.connections();
+
+        // The three power lines are edges, not nodes
+        assert_eq!(graph.nodes.len(), 22);
+        assert_eq!(count(&graph, ConnectionKind::Power), 3);
+        assert_ne!(count(&graph, ConnectionKind::Belt), 0);
+        assert_ne!(count(&graph, ConnectionKind::Pipe), 0);
+        assert_eq!(graph.dangling, []);
+
+        let pole = graph
+            .nodes
+            .iter()
+            .position(|node| node.contains("Build_PowerPoleMk1_C"))
+            .expect("Blueprint should have a power pole");
+        let powered = graph.reachable(pole, &[ConnectionKind::Power]);
+        assert_eq!(powered.len(), 4);
+        assert!(
+            powered[1..]
+                .iter()
+                .all(|node| graph.nodes[*node].contains("Build_GeneratorCoal_C"))
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph blueprint {\n"));
+        assert_eq!(dot.matches(" -- ").count(), graph.edges.len());
+        assert!(dot.contains("color=gold"));
+    }
+
+    #[test]
+    fn check_dangling_connections() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let pole = body
+            .query()
+            .type_path("*Build_PowerPoleMk1_C")
+            .iter()
+            .next()
+            .map(|entity| entity.instance_name().to_owned())
+            .expect("Blueprint should have a power pole");
+        body.remove(&pole);
+
+        let graph = body.connections();
+        assert_eq!(count(&graph, ConnectionKind::Power), 0);
+        assert_eq!(graph.dangling.len(), 3);
+        assert!(
+            graph
+                .dangling
+                .iter()
+                .all(|dangling| dangling.kind == ConnectionKind::Power
+                    && dangling.target.contains("Build_PowerPoleMk1_C"))
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use color_eyre::Result;
+
+use crate::patterns::body::{
+    ActorObject, LinearColor, PropertyList, StructDataType, Value, class_name,
+};
+
+/// Property holding the paint of a building
+pub const CUSTOMIZATION_PROPERTY: &str = "mCustomizationData";
+/// Struct type of `mCustomizationData`
+pub const CUSTOMIZATION_DATA: &str = "FactoryCustomizationData\0";
+/// Struct type of the colours overriding the swatch
+pub const COLOR_SLOT: &str = "FactoryCustomizationColorSlot\0";
+/// Folder of the swatch descriptors, `SwatchDesc_Slot<n>` and `SwatchDesc_Custom`
+pub const SWATCH_PATH: &str = "/Game/FactoryGame/Buildable/-Shared/Customization/Swatches";
+
+const SWATCH: &str = "SwatchDesc";
+const MATERIAL: &str = "MaterialDesc";
+const PATTERN: &str = "PatternDesc";
+const SKIN: &str = "SkinDesc";
+const OVERRIDE_COLOR: &str = "OverrideColorData";
+const PRIMARY_COLOR: &str = "PrimaryColor";
+const SECONDARY_COLOR: &str = "SecondaryColor";
+
+const SLOT_PREFIX: &str = "SwatchDesc_Slot";
+const CUSTOM_SWATCH: &str = "SwatchDesc_Custom";
+
+/// Reference to the swatch descriptor of a class such as `SwatchDesc_Slot3`.
+fn swatch_reference(swatch: &str) -> String {
+    format!("{SWATCH_PATH}/{swatch}.{swatch}_C\0")
+}
+
+/// Paint and skin of a building, read from its `mCustomizationData` struct. Descriptors are
+/// references including the trailing nul, missing values are `None`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CustomizationData {
+    pub swatch: Option<String>,
+    pub primary_color: Option<LinearColor>,
+    pub secondary_color: Option<LinearColor>,
+    pub material: Option<String>,
+    pub pattern: Option<String>,
+    pub skin: Option<String>,
+}
+
+impl CustomizationData {
+    /// Reads the customization data of an object, which is empty if it has none.
+    pub fn read(properties: &PropertyList) -> Self {
+        let Some(data) = properties.get::<&PropertyList>(CUSTOMIZATION_PROPERTY) else {
+            return Self::default();
+        };
+        let reference = |name| data.get::<&str>(name).map(ToOwned::to_owned);
+
+        CustomizationData {
+            swatch: reference(SWATCH),
+            primary_color: data.get(&format!("{OVERRIDE_COLOR}.{PRIMARY_COLOR}")),
+            secondary_color: data.get(&format!("{OVERRIDE_COLOR}.{SECONDARY_COLOR}")),
+            material: reference(MATERIAL),
+            pattern: reference(PATTERN),
+            skin: reference(SKIN),
+        }
+    }
+
+    /// Writes the values back into the `mCustomizationData` struct of `properties`, creating it
+    /// if needed. Properties of the struct this view does not cover are kept.
+    pub fn write(&self, properties: &mut PropertyList) -> Result<()> {
+        if properties.find(CUSTOMIZATION_PROPERTY).is_none() {
+            if *self == Self::default() {
+                return Ok(());
+            }
+
+            properties.insert(CUSTOMIZATION_PROPERTY, empty_struct(CUSTOMIZATION_DATA))?;
+        }
+
+        let path = |name| format!("{CUSTOMIZATION_PROPERTY}.{name}");
+        let references = [
+            (SWATCH, &self.swatch),
+            (MATERIAL, &self.material),
+            (PATTERN, &self.pattern),
+            (SKIN, &self.skin),
+        ];
+        for (name, reference) in references {
+            update(properties, &path(name), reference.as_deref())?;
+        }
+
+        let colors_path = path(OVERRIDE_COLOR);
+        let has_colors = self.primary_color.is_some() || self.secondary_color.is_some();
+        if has_colors && properties.find_path(&colors_path).is_none() {
+            properties.insert(&colors_path, empty_struct(COLOR_SLOT))?;
+        }
+        if properties.find_path(&colors_path).is_some() {
+            let colors = [
+                (PRIMARY_COLOR, self.primary_color),
+                (SECONDARY_COLOR, self.secondary_color),
+            ];
+            for (name, color) in colors {
+                update(properties, &format!("{colors_path}.{name}"), color)?;
+            }
+
+            if properties
+                .get::<&PropertyList>(&colors_path)
+                .is_some_and(|colors| colors.0.is_empty())
+            {
+                properties.remove(&colors_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number of the colour gun slot of the swatch, `None` for custom colours.
+    pub fn swatch_slot(&self) -> Option<u32> {
+        class_name(self.swatch.as_deref()?)
+            .strip_prefix(SLOT_PREFIX)?
+            .strip_suffix("_C")?
+            .parse()
+            .ok()
+    }
+
+    pub fn set_swatch_slot(&mut self, slot: u32) {
+        self.swatch = Some(swatch_reference(&format!("{SLOT_PREFIX}{slot}")));
+    }
+
+    /// Paints the building with custom colours instead of a slot.
+    pub fn set_custom_colors(&mut self, primary: LinearColor, secondary: Option<LinearColor>) {
+        self.swatch = Some(swatch_reference(CUSTOM_SWATCH));
+        self.primary_color = Some(primary);
+        self.secondary_color = secondary;
+    }
+}
+
+pub(crate) fn empty_struct(name: &str) -> StructDataType {
+    StructDataType::Other {
+        name: name.to_owned(),
+        list: PropertyList(Vec::new()),
+    }
+}
+
+/// Sets, inserts or removes the property at `path` so it holds `value`.
+pub(crate) fn update(
+    properties: &mut PropertyList,
+    path: &str,
+    value: Option<impl Into<Value>>,
+) -> Result<()> {
+    match value {
+        Some(value) if properties.find_path(path).is_some() => properties.set(path, value),
+        Some(value) => properties.insert(path, value),
+        None => {
+            properties.remove(path);
+            Ok(())
+        }
+    }
+}
+
+impl ActorObject {
+    pub fn customization(&self) -> CustomizationData {
+        CustomizationData::read(&self.properties)
+    }
+
+    /// See [`CustomizationData::write`].
+    pub fn set_customization(&mut self, customization: &CustomizationData) -> Result<()> {
+        customization.write(&mut self.properties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::{Blueprint, body::ObjectType};
+
+    #[test]
+    fn check_customization() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let ObjectType::Actor(generator) = &blueprint.body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+
+        let customization = generator.customization();
+        assert!(customization.swatch_slot().is_some());
+        assert_eq!(customization.primary_color, None);
+
+        // Writing back unchanged data leaves the properties as they were
+        let mut edited = generator.clone();
+        edited
+            .set_customization(&customization)
+            .expect("Write should succeed");
+        assert_eq!(&edited, generator);
+
+        let color = LinearColor {
+            r: 0.25,
+            g: 0.5,
+            b: 1.0,
+            a: 1.0,
+        };
+        let mut custom = customization.clone();
+        custom.set_custom_colors(color, None);
+        custom.skin = Some("/Game/Skin.Skin_C\0".to_owned());
+        edited
+            .set_customization(&custom)
+            .expect("Write should succeed");
+        assert_eq!(edited.customization(), custom);
+        assert_eq!(edited.customization().swatch_slot(), None);
+
+        let mut slot = custom.clone();
+        slot.set_swatch_slot(5);
+        slot.primary_color = None;
+        slot.skin = None;
+        edited
+            .set_customization(&slot)
+            .expect("Write should succeed");
+        assert_eq!(edited.customization().swatch_slot(), Some(5));
+        assert_eq!(edited.customization(), slot);
+        assert!(
+            edited
+                .properties
+                .find_path("mCustomizationData.OverrideColorData")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn check_new_customization() {
+        let mut properties = PropertyList(Vec::new());
+        CustomizationData::default()
+            .write(&mut properties)
+            .expect("Write should succeed");
+        assert!(properties.0.is_empty());
+
+        let mut customization = CustomizationData::default();
+        customization.set_swatch_slot(0);
+        customization
+            .write(&mut properties)
+            .expect("Write should succeed");
+        assert_eq!(CustomizationData::read(&properties), customization);
+        assert_eq!(
+            properties
+                .get::<&StructDataType>(CUSTOMIZATION_PROPERTY)
+                .map(StructDataType::name),
+            Some(CUSTOMIZATION_DATA)
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use color_eyre::eyre::bail;
+
+use crate::patterns::body::{BlueprintBody, ObjectHeaderType, ObjectRef, ObjectType};
+
+/// An object header together with its object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entity<'b> {
+    pub header: &'b ObjectHeaderType,
+    pub object: &'b ObjectType,
+}
+
+impl Entity<'_> {
+    pub fn instance_name(&self) -> &str {
+        self.header.instance_name()
+    }
+}
+
+/// Mutable access to an object header together with its object.
+#[derive(Debug, PartialEq)]
+pub struct EntityMut<'b> {
+    pub header: &'b mut ObjectHeaderType,
+    pub object: &'b mut ObjectType,
+}
+
+impl EntityMut<'_> {
+    pub fn instance_name(&self) -> &str {
+        self.header.instance_name()
+    }
+}
+
+/// Whether a header and an object are both for an actor or both for a component.
+pub fn kinds_match(header: &ObjectHeaderType, object: &ObjectType) -> bool {
+    matches!(
+        (header, object),
+        (ObjectHeaderType::Actor(_), ObjectType::Actor(_))
+            | (ObjectHeaderType::Component(_), ObjectType::Component(_))
+    )
+}
+
+impl BlueprintBody {
+    pub fn entities(&self) -> impl Iterator<Item = Entity<'_>> {
+        self.object_headers
+            .iter()
+            .zip(&self.objects)
+            .map(|(header, object)| Entity { header, object })
+    }
+
+    pub fn entities_mut(&mut self) -> impl Iterator<Item = EntityMut<'_>> {
+        self.object_headers
+            .iter_mut()
+            .zip(&mut self.objects)
+            .map(|(header, object)| EntityMut { header, object })
+    }
+
+    fn position(&self, instance_name: &str) -> Option<usize> {
+        self.object_headers
+            .iter()
+            .position(|header| header.instance_name() == instance_name)
+    }
+
+    pub fn entity(&self, instance_name: &str) -> Option<Entity<'_>> {
+        self.entities()
+            .find(|entity| entity.instance_name() == instance_name)
+    }
+
+    pub fn entity_mut(&mut self, instance_name: &str) -> Option<EntityMut<'_>> {
+        self.entities_mut()
+            .find(|entity| entity.instance_name() == instance_name)
+    }
+
+    /// Adds an object with its header. Actors are placed after the other actors and components
+    /// after everything else, so components always follow their actor. A component is added to
+    /// the component list of its actor unless it is listed already.
+    pub fn insert(
+        &mut self,
+        header: ObjectHeaderType,
+        object: ObjectType,
+    ) -> color_eyre::Result<()> {
+        if !kinds_match(&header, &object) {
+            bail!(
+                "Header and object of {:?} are of different kinds",
+                header.instance_name()
+            );
+        }
+        if self.position(header.instance_name()).is_some() {
+            bail!("Instance name {:?} is already used", header.instance_name());
+        }
+
+        let index = match &header {
+            ObjectHeaderType::Actor(_) => self
+                .object_headers
+                .iter()
+                .position(|header| matches!(header, ObjectHeaderType::Component(_)))
+                .unwrap_or(self.object_headers.len()),
+            ObjectHeaderType::Component(component) => {
+                let Some(actor) = self.position(&component.parent_actor) else {
+                    bail!(
+                        "Actor {:?} of component {:?} is not in the body",
+                        component.parent_actor,
+                        component.instance_name
+                    );
+                };
+
+                if let ObjectType::Actor(actor) = &mut self.objects[actor]
+                    && !actor
+                        .components
+                        .iter()
+                        .any(|listed| listed.path_name == component.instance_name)
+                {
+                    actor.components.push(ObjectRef {
+                        level_name: component.root_object.clone(),
+                        path_name: component.instance_name.clone(),
+                    });
+                }
+
+                self.object_headers.len()
+            }
+        };
+
+        self.object_headers.insert(index, header);
+        self.objects.insert(index, object);
+
+        Ok(())
+    }
+
+    /// Removes an object with its header. Removing an actor also removes its components, while a
+    /// component removed on its own is taken off the component list of its actor.
+    /// Returns everything that was removed, or nothing if the instance name is not in the body.
+    pub fn remove(&mut self, instance_name: &str) -> Vec<(ObjectHeaderType, ObjectType)> {
+        let Some(index) = self.position(instance_name) else {
+            return Vec::new();
+        };
+
+        let mut removed = vec![(
+            self.object_headers.remove(index),
+            self.objects.remove(index),
+        )];
+        match &removed[0] {
+            (ObjectHeaderType::Actor(actor), _) => {
+                let actor_name = actor.instance_name.clone();
+                while let Some(index) = self.object_headers.iter().position(|header| {
+                    matches!(header, ObjectHeaderType::Component(component) if component.parent_actor == actor_name)
+                }) {
+                    removed.push((self.object_headers.remove(index), self.objects.remove(index)));
+                }
+            }
+            (ObjectHeaderType::Component(component), _) => {
+                if let Some(ObjectType::Actor(actor)) = self
+                    .position(&component.parent_actor)
+                    .map(|index| &mut self.objects[index])
+                {
+                    actor
+                        .components
+                        .retain(|listed| listed.path_name != component.instance_name);
+                }
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    #[test]
+    fn check_entities() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+        const GENERATOR: &str =
+            "Persistent_Level:PersistentLevel.Build_GeneratorCoal_C_2147468445\0";
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        assert!(
+            body.entities()
+                .all(|entity| kinds_match(entity.header, entity.object))
+        );
+
+        let generator = body.entity(GENERATOR).expect("Generator should exist");
+        let ObjectType::Actor(actor) = generator.object else {
+            panic!("Expected an actor object");
+        };
+        assert_eq!(actor.components.len(), 7);
+
+        let removed = body.remove(GENERATOR);
+        assert_eq!(removed.len(), 8);
+        assert_eq!(body.object_headers.len(), 89);
+        assert_eq!(body.objects.len(), 89);
+        assert!(body.entity(GENERATOR).is_none());
+
+        // Putting the actor back before its components keeps the body in order
+        let mut removed = removed.into_iter();
+        let (header, object) = removed.next().expect("Actor should be removed first");
+        body.insert(header.clone(), object.clone())
+            .expect("Insert should succeed");
+        assert!(body.insert(header, object).is_err());
+        for (header, object) in removed {
+            body.insert(header, object).expect("Insert should succeed");
+        }
+        assert_eq!(body.validate_names(), []);
+
+        let (header, _) = body.remove(GENERATOR).swap_remove(1);
+        let ObjectType::Actor(actor) = body.objects[0].clone() else {
+            panic!("Expected an actor object");
+        };
+        assert!(body.insert(header, ObjectType::Actor(actor)).is_err());
+    }
+
+    #[test]
+    fn check_component_list() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+        const GENERATOR: &str =
+            "Persistent_Level:PersistentLevel.Build_GeneratorCoal_C_2147468445\0";
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let components = |body: &BlueprintBody| {
+            let ObjectType::Actor(actor) = body
+                .entity(GENERATOR)
+                .expect("Generator should exist")
+                .object
+            else {
+                panic!("Expected an actor object");
+            };
+            actor.components.clone()
+        };
+        let listed = components(&body);
+
+        // A component removed on its own leaves its actor's list
+        let (header, object) = body
+            .remove(&listed[0].path_name)
+            .pop()
+            .expect("Component should be removed");
+        assert_eq!(components(&body), listed[1..]);
+        assert_eq!(body.validate_names(), []);
+
+        // and inserting it lists it again
+        body.insert(header, object).expect("Insert should succeed");
+        let mut relisted = listed[1..].to_vec();
+        relisted.push(listed[0].clone());
+        assert_eq!(components(&body), relisted);
+        assert_eq!(body.validate_names(), []);
+    }
+}
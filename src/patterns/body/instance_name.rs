@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::patterns::body::{BlueprintBody, ObjectHeaderType, ObjectType};
+
+/// Level every blueprint object is stored in
+pub const LEVEL_NAME: &str = "Persistent_Level\0";
+/// Prefix of the instance name of every object in the level
+pub const LEVEL_PATH: &str = "Persistent_Level:PersistentLevel.";
+/// Object every buildable in a blueprint belongs to
+pub const BUILDABLE_SUBSYSTEM: &str = "Persistent_Level:PersistentLevel.BuildableSubsystem\0";
+
+/// Numbers the game gives new objects count down from here
+const FIRST_NUMBER: u32 = i32::MAX as u32;
+
+/// Class name of a type path, e.g. `Build_Beam_Painted_C` for
+/// `/Game/FactoryGame/Prototype/Buildable/Beams/Build_Beam_Painted.Build_Beam_Painted_C`.
+pub fn class_name(type_path: &str) -> &str {
+    let type_path = type_path.trim_end_matches('\0');
+
+    type_path
+        .rsplit_once('.')
+        .map_or(type_path, |(_, class)| class)
+}
+
+/// Whether `type_path` is a building or another buildable, which the buildable subsystem owns.
+fn is_buildable(type_path: &str) -> bool {
+    type_path.contains("/Buildable/")
+}
+
+/// Number at the end of an instance name, if there is one.
+fn instance_number(instance_name: &str) -> Option<u32> {
+    instance_name
+        .trim_end_matches('\0')
+        .rsplit_once('_')
+        .and_then(|(_, number)| number.parse().ok())
+}
+
+/// Hands out instance names such as
+/// `Persistent_Level:PersistentLevel.Build_Beam_Painted_C_2145391819`, which are unique within the
+/// body the allocator was created from. Like the game, numbers count down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceNames {
+    taken: HashSet<String>,
+    next: u32,
+}
+
+impl InstanceNames {
+    pub fn new(body: &BlueprintBody) -> Self {
+        let taken: HashSet<String> = body
+            .object_headers
+            .iter()
+            .map(|header| header.instance_name().to_owned())
+            .collect();
+        let next = taken
+            .iter()
+            .filter_map(|name| instance_number(name))
+            .min()
+            .map_or(FIRST_NUMBER, |number| number.saturating_sub(1));
+
+        InstanceNames { taken, next }
+    }
+
+    pub fn contains(&self, instance_name: &str) -> bool {
+        self.taken.contains(instance_name)
+    }
+
+    /// Reserves an existing name so it is never handed out. Returns false if it was taken.
+    pub fn reserve(&mut self, instance_name: &str) -> bool {
+        self.taken.insert(instance_name.to_owned())
+    }
+
+    /// Mints a new instance name for an actor of class `type_path`. Once the numbers below every
+    /// existing name run out, free numbers are looked for from the top again.
+    pub fn allocate(&mut self, type_path: &str) -> String {
+        let class = class_name(type_path);
+
+        loop {
+            let name = format!("{LEVEL_PATH}{class}_{}\0", self.next);
+            self.next = self.next.checked_sub(1).unwrap_or(FIRST_NUMBER);
+
+            if self.taken.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+}
+
+/// Inconsistency found by [`BlueprintBody::validate_names`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    /// More than one object uses the instance name
+    Duplicate(String),
+    /// The parent object of an actor is in a different level than the actor
+    ParentMismatch {
+        instance_name: String,
+        parent_object: String,
+    },
+    /// An actor is owned by neither another actor of the body nor, for buildables, the buildable
+    /// subsystem
+    WrongOwner {
+        instance_name: String,
+        parent_object: String,
+    },
+    /// A component names an actor that is not in the body
+    MissingActor {
+        instance_name: String,
+        parent_actor: String,
+    },
+    /// A component is not in the component list of its actor
+    UnlistedComponent {
+        instance_name: String,
+        parent_actor: String,
+    },
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Duplicate(name) => {
+                write!(f, "instance name {name:?} is used more than once")
+            }
+            NameError::ParentMismatch {
+                instance_name,
+                parent_object,
+            } => write!(
+                f,
+                "actor {instance_name:?} has parent {parent_object:?} from another level"
+            ),
+            NameError::WrongOwner {
+                instance_name,
+                parent_object,
+            } => write!(
+                f,
+                "actor {instance_name:?} is owned by unexpected object {parent_object:?}"
+            ),
+            NameError::MissingActor {
+                instance_name,
+                parent_actor,
+            } => write!(
+                f,
+                "component {instance_name:?} belongs to missing actor {parent_actor:?}"
+            ),
+            NameError::UnlistedComponent {
+                instance_name,
+                parent_actor,
+            } => write!(
+                f,
+                "component {instance_name:?} is not listed by its actor {parent_actor:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+impl BlueprintBody {
+    /// Creates an allocator for instance names that are not used in this body.
+    pub fn instance_names(&self) -> InstanceNames {
+        InstanceNames::new(self)
+    }
+
+    /// Checks that instance names are unique, that each actor's parent object is in the level of
+    /// its header and is the buildable subsystem or, for attachments such as pipe flow
+    /// indicators, another actor of the body. Components must belong to an actor of the body
+    /// which lists them.
+    pub fn validate_names(&self) -> Vec<NameError> {
+        let mut errors = Vec::new();
+
+        let mut seen = HashSet::new();
+        for header in &self.object_headers {
+            if !seen.insert(header.instance_name()) {
+                errors.push(NameError::Duplicate(header.instance_name().to_owned()));
+            }
+        }
+
+        let actors: HashSet<&str> = self
+            .actor_headers()
+            .map(|actor| actor.instance_name.as_str())
+            .collect();
+        // Components listed by each actor, as (actor, component) pairs
+        let mut listed = HashSet::new();
+
+        for (header, object) in self.object_headers.iter().zip(&self.objects) {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) = (header, object)
+            else {
+                continue;
+            };
+
+            let parent = &actor.parent_object;
+            if parent.level_name != header.root_object {
+                errors.push(NameError::ParentMismatch {
+                    instance_name: header.instance_name.clone(),
+                    parent_object: parent.path_name.clone(),
+                });
+            } else if !(actors.contains(parent.path_name.as_str())
+                || is_buildable(&header.type_path) && parent.path_name == BUILDABLE_SUBSYSTEM)
+            {
+                errors.push(NameError::WrongOwner {
+                    instance_name: header.instance_name.clone(),
+                    parent_object: parent.path_name.clone(),
+                });
+            }
+
+            listed.extend(
+                actor
+                    .components
+                    .iter()
+                    .map(|component| (header.instance_name.as_str(), component.path_name.as_str())),
+            );
+        }
+
+        for header in &self.object_headers {
+            let ObjectHeaderType::Component(component) = header else {
+                continue;
+            };
+
+            if !actors.contains(component.parent_actor.as_str()) {
+                errors.push(NameError::MissingActor {
+                    instance_name: component.instance_name.clone(),
+                    parent_actor: component.parent_actor.clone(),
+                });
+            } else if !listed.contains(&(
+                component.parent_actor.as_str(),
+                component.instance_name.as_str(),
+            )) {
+                errors.push(NameError::UnlistedComponent {
+                    instance_name: component.instance_name.clone(),
+                    parent_actor: component.parent_actor.clone(),
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    #[test]
+    fn check_instance_names() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+        const TYPE_PATH: &str =
+            "/Game/FactoryGame/Prototype/Buildable/Beams/Build_Beam_Painted.Build_Beam_Painted_C\0";
+
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        assert_eq!(blueprint.body.validate_names(), []);
+
+        let mut names = blueprint.body.instance_names();
+        let first = names.allocate(TYPE_PATH);
+        let second = names.allocate(TYPE_PATH);
+
+        assert!(first.starts_with("Persistent_Level:PersistentLevel.Build_Beam_Painted_C_"));
+        assert!(first.ends_with('\0'));
+        assert_ne!(first, second);
+        assert!(names.contains(&first));
+        for header in &blueprint.body.object_headers {
+            assert_ne!(header.instance_name(), first);
+            assert_ne!(header.instance_name(), second);
+        }
+    }
+
+    #[test]
+    fn check_low_instance_numbers() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let ObjectHeaderType::Actor(actor) = &mut body.object_headers[0] else {
+            panic!("Expected an actor header");
+        };
+        let type_path = actor.type_path.clone();
+        let class = class_name(&type_path);
+        actor.instance_name = format!("{LEVEL_PATH}{class}_0\0");
+
+        // Numbers start over from the top rather than going below zero
+        let mut names = body.instance_names();
+        assert_eq!(
+            names.allocate(&type_path),
+            format!("{LEVEL_PATH}{class}_{FIRST_NUMBER}\0")
+        );
+        assert_eq!(
+            names.allocate(&type_path),
+            format!("{LEVEL_PATH}{class}_{}\0", FIRST_NUMBER - 1)
+        );
+    }
+
+    #[test]
+    fn check_validate_names() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let duplicate = body.object_headers[0].clone();
+        let name = duplicate.instance_name().to_owned();
+        body.object_headers.push(duplicate);
+        if let ObjectType::Actor(actor) = &mut body.objects[1] {
+            actor.parent_object.level_name = "Other_Level\0".to_owned();
+        }
+        let ObjectHeaderType::Actor(owned) = &body.object_headers[2] else {
+            panic!("Expected an actor header");
+        };
+        let owned = owned.instance_name.clone();
+        if let ObjectType::Actor(actor) = &mut body.objects[2] {
+            actor.parent_object.path_name = format!("{LEVEL_PATH}Unknown\0");
+        }
+
+        let errors = body.validate_names();
+        assert!(errors.contains(&NameError::Duplicate(name)));
+        assert!(
+            errors
+                .iter()
+                .any(|error| matches!(error, NameError::ParentMismatch { .. }))
+        );
+        assert!(errors.contains(&NameError::WrongOwner {
+            instance_name: owned,
+            parent_object: format!("{LEVEL_PATH}Unknown\0"),
+        }));
+    }
+}
@@ -0,0 +1,345 @@
+use color_eyre::Result;
+
+use crate::patterns::body::{
+    ActorObject, ArrayValue, BlueprintBody, LEVEL_NAME, ObjectHeaderType, ObjectRef, ObjectType,
+    PropertyList, class_name, empty_struct, glob_match, update,
+};
+
+/// Property holding the intensity and colour of a light or a light control panel
+pub const LIGHT_CONTROL_DATA: &str = "mLightControlData";
+/// Struct type of `mLightControlData`
+pub const LIGHT_SOURCE_CONTROL_DATA: &str = "LightSourceControlData\0";
+/// Whether a light only turns on at night
+pub const IS_TIME_OF_DAY_AWARE: &str = "mIsTimeOfDayAware";
+pub const IS_ENABLED: &str = "mIsEnabled";
+/// Lights a light control panel controls
+pub const CONTROLLED_BUILDABLES: &str = "mControlledBuildables";
+
+const INTENSITY: &str = "Intensity";
+const COLOR_SLOT_INDEX: &str = "ColorSlotIndex";
+
+/// Classes of the buildings giving light
+const LIGHTS: [&str; 4] = [
+    "Build_CeilingLight_C",
+    "Build_FloodlightPole_C",
+    "Build_FloodlightWall_C",
+    "Build_StreetLight_C",
+];
+const LIGHTS_CONTROL_PANEL: &str = "Build_LightsControlPanel_C";
+
+pub fn is_light(type_path: &str) -> bool {
+    LIGHTS.contains(&class_name(type_path))
+}
+
+pub fn is_light_control_panel(type_path: &str) -> bool {
+    class_name(type_path) == LIGHTS_CONTROL_PANEL
+}
+
+/// Settings of a light or a light control panel. Missing values are `None` and take the
+/// defaults of the game.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LightData {
+    /// Brightness between 0 and 1
+    pub intensity: Option<f32>,
+    /// Index of the light colour slot
+    pub color_slot: Option<i32>,
+    pub time_of_day_aware: Option<bool>,
+    pub enabled: Option<bool>,
+    /// Instance names of the lights a control panel controls
+    pub controlled_lights: Vec<String>,
+}
+
+impl LightData {
+    pub fn read(properties: &PropertyList) -> Self {
+        let control = |name| format!("{LIGHT_CONTROL_DATA}.{name}");
+        let controlled_lights = match properties.get::<&ArrayValue>(CONTROLLED_BUILDABLES) {
+            Some(ArrayValue::Object(refs)) => refs
+                .iter()
+                .map(|object_ref| object_ref.path_name.clone())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        LightData {
+            intensity: properties.get(&control(INTENSITY)),
+            color_slot: properties.get(&control(COLOR_SLOT_INDEX)),
+            time_of_day_aware: properties.get(IS_TIME_OF_DAY_AWARE),
+            enabled: properties.get(IS_ENABLED),
+            controlled_lights,
+        }
+    }
+
+    /// Writes the settings back into `properties`, creating `mLightControlData` if needed.
+    /// Properties this view does not cover are kept.
+    pub fn write(&self, properties: &mut PropertyList) -> Result<()> {
+        let has_control = self.intensity.is_some() || self.color_slot.is_some();
+        if has_control && properties.find(LIGHT_CONTROL_DATA).is_none() {
+            properties.insert(LIGHT_CONTROL_DATA, empty_struct(LIGHT_SOURCE_CONTROL_DATA))?;
+        }
+        if properties.find(LIGHT_CONTROL_DATA).is_some() {
+            let control = |name| format!("{LIGHT_CONTROL_DATA}.{name}");
+            update(properties, &control(INTENSITY), self.intensity)?;
+            update(properties, &control(COLOR_SLOT_INDEX), self.color_slot)?;
+        }
+
+        update(properties, IS_TIME_OF_DAY_AWARE, self.time_of_day_aware)?;
+        update(properties, IS_ENABLED, self.enabled)?;
+
+        let controlled = (!self.controlled_lights.is_empty()).then(|| {
+            ArrayValue::Object(
+                self.controlled_lights
+                    .iter()
+                    .map(|light| ObjectRef {
+                        level_name: LEVEL_NAME.to_owned(),
+                        path_name: light.clone(),
+                    })
+                    .collect(),
+            )
+        });
+        update(properties, CONTROLLED_BUILDABLES, controlled)
+    }
+}
+
+impl ActorObject {
+    pub fn light(&self) -> LightData {
+        LightData::read(&self.properties)
+    }
+
+    /// See [`LightData::write`].
+    pub fn set_light(&mut self, light: &LightData) -> Result<()> {
+        light.write(&mut self.properties)
+    }
+}
+
+/// A light or a light control panel of a blueprint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    pub instance_name: String,
+    pub type_path: String,
+    pub data: LightData,
+}
+
+/// Changes the settings of the lights of a blueprint, see [`BlueprintBody::edit_lights`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LightingEdit {
+    intensity: Option<f32>,
+    color_slot: Option<i32>,
+    time_of_day_aware: Option<bool>,
+    enabled: Option<bool>,
+    type_path: Option<String>,
+}
+
+impl LightingEdit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the brightness, clamped between 0 and 1.
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = Some(intensity.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn color_slot(mut self, slot: i32) -> Self {
+        self.color_slot = Some(slot);
+        self
+    }
+
+    pub fn time_of_day_aware(mut self, aware: bool) -> Self {
+        self.time_of_day_aware = Some(aware);
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// Only edits buildings whose type path matches the glob `pattern`, see [`glob_match`].
+    pub fn type_path(mut self, pattern: &str) -> Self {
+        self.type_path = Some(pattern.to_owned());
+        self
+    }
+}
+
+impl BlueprintBody {
+    fn lighting(&self, filter: fn(&str) -> bool) -> Vec<Light> {
+        self.entities()
+            .filter_map(|entity| match (entity.header, entity.object) {
+                (ObjectHeaderType::Actor(header), ObjectType::Actor(actor))
+                    if filter(&header.type_path) =>
+                {
+                    Some(Light {
+                        instance_name: header.instance_name.clone(),
+                        type_path: header.type_path.clone(),
+                        data: actor.light(),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn lights(&self) -> Vec<Light> {
+        self.lighting(is_light)
+    }
+
+    pub fn light_control_panels(&self) -> Vec<Light> {
+        self.lighting(is_light_control_panel)
+    }
+
+    /// Applies `edit` to the lights and light control panels matching its type path. Returns
+    /// the number of buildings that changed.
+    pub fn edit_lights(&mut self, edit: &LightingEdit) -> Result<usize> {
+        let mut edited = 0;
+
+        for entity in self.entities_mut() {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                (&*entity.header, entity.object)
+            else {
+                continue;
+            };
+            if !is_light(&header.type_path) && !is_light_control_panel(&header.type_path) {
+                continue;
+            }
+            if edit
+                .type_path
+                .as_ref()
+                .is_some_and(|pattern| !glob_match(pattern, &header.type_path))
+            {
+                continue;
+            }
+
+            let before = actor.light();
+            let mut light = before.clone();
+            light.intensity = edit.intensity.or(light.intensity);
+            light.color_slot = edit.color_slot.or(light.color_slot);
+            light.time_of_day_aware = edit.time_of_day_aware.or(light.time_of_day_aware);
+            light.enabled = edit.enabled.or(light.enabled);
+
+            if light != before {
+                actor.set_light(&light)?;
+                edited += 1;
+            }
+        }
+
+        Ok(edited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{
+            Blueprint,
+            body::{ActorHeader, blueprint_body},
+        },
+        transform::{Transform, Vec3},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+    const CEILING_LIGHT: &str = "/Game/FactoryGame/Buildable/Factory/CeilingLight/Build_CeilingLight.Build_CeilingLight_C\0";
+    const CONTROL_PANEL: &str = "/Game/FactoryGame/Buildable/Factory/LightsControlPanel/Build_LightsControlPanel.Build_LightsControlPanel_C\0";
+
+    fn add(body: &mut BlueprintBody, type_path: &str, light: &LightData) -> String {
+        let instance_name = body.instance_names().allocate(type_path);
+        let mut actor = ActorObject::buildable(PropertyList(Vec::new()));
+        actor.set_light(light).expect("Light should be written");
+        let header = ActorHeader::new(
+            type_path,
+            instance_name.clone(),
+            &Transform::from_translation(Vec3::ZERO),
+        );
+        body.insert(ObjectHeaderType::Actor(header), ObjectType::Actor(actor))
+            .expect("Light should be inserted");
+
+        instance_name
+    }
+
+    /// A blueprint with two ceiling lights, one of them controlled by a panel.
+    fn lit() -> (BlueprintBody, [String; 3]) {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let first = add(
+            &mut body,
+            CEILING_LIGHT,
+            &LightData {
+                intensity: Some(0.5),
+                color_slot: Some(2),
+                time_of_day_aware: Some(true),
+                ..LightData::default()
+            },
+        );
+        let second = add(&mut body, CEILING_LIGHT, &LightData::default());
+        let panel = add(
+            &mut body,
+            CONTROL_PANEL,
+            &LightData {
+                enabled: Some(true),
+                controlled_lights: vec![first.clone()],
+                ..LightData::default()
+            },
+        );
+
+        (body, [first, second, panel])
+    }
+
+    #[test]
+    fn check_lights() {
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        assert_eq!(body.lights(), []);
+
+        let (body, [first, second, panel]) = lit();
+        let lights = body.lights();
+        assert_eq!(lights.len(), 2);
+        assert_eq!(lights[0].instance_name, first);
+        assert_eq!(lights[0].data.intensity, Some(0.5));
+        assert_eq!(lights[0].data.color_slot, Some(2));
+        assert_eq!(lights[0].data.time_of_day_aware, Some(true));
+        assert_eq!(lights[1].instance_name, second);
+        assert_eq!(lights[1].data, LightData::default());
+
+        let panels = body.light_control_panels();
+        assert_eq!(panels.len(), 1);
+        assert_eq!(panels[0].instance_name, panel);
+        assert_eq!(panels[0].data.enabled, Some(true));
+        assert_eq!(panels[0].data.controlled_lights, [first]);
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+    }
+
+    #[test]
+    fn check_edit_lights() {
+        let (mut body, _) = lit();
+
+        let edit = LightingEdit::new()
+            .intensity(1.5)
+            .color_slot(4)
+            .type_path("*Build_CeilingLight_C");
+        assert_eq!(body.edit_lights(&edit).expect("Edit should succeed"), 2);
+        assert!(body.lights().iter().all(|light| {
+            light.data.intensity == Some(1.0) && light.data.color_slot == Some(4)
+        }));
+        assert_eq!(body.lights()[0].data.time_of_day_aware, Some(true));
+        assert_eq!(body.light_control_panels()[0].data.intensity, None);
+
+        // Nothing changes the second time
+        assert_eq!(body.edit_lights(&edit).expect("Edit should succeed"), 0);
+
+        let edit = LightingEdit::new().enabled(false);
+        assert_eq!(body.edit_lights(&edit).expect("Edit should succeed"), 3);
+        assert!(
+            body.lights()
+                .iter()
+                .chain(&body.light_control_panels())
+                .all(|light| light.data.enabled == Some(false))
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use crate::{
+    patterns::body::{
+        BlueprintBody, ObjectHeaderType, RecipeSwap, StructDataType, WORLD_POSITIONS,
+    },
+    transform::{Quat, Vec3},
+};
+
+/// Property holding a scale, which must stay positive when mirroring
+const SCALE: &str = "Scale3D\0";
+
+/// World axis that is negated by a mirror.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorAxis {
+    X,
+    Y,
+}
+
+impl MirrorAxis {
+    pub fn normal(self) -> Vec3 {
+        match self {
+            MirrorAxis::X => Vec3::X,
+            MirrorAxis::Y => Vec3::Y,
+        }
+    }
+
+    /// Rotation of a building that is symmetric across its local Y axis after mirroring it.
+    pub fn mirror_rotation(self, rotation: Quat) -> Quat {
+        match self {
+            MirrorAxis::X => rotation.mirror(Vec3::X) * Quat::from_yaw(180.0),
+            MirrorAxis::Y => rotation.mirror(Vec3::Y),
+        }
+    }
+}
+
+/// Pairs of buildings that are mirror images of each other across their local Y axis, such as
+/// left and right handed variants, each given as its type path and the recipe it is built with.
+/// Paths include the trailing nul like the rest of the body.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MirrorTable<'t> {
+    swap: RecipeSwap<'t>,
+}
+
+impl<'t> MirrorTable<'t> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pair of handed buildings, each one is replaced by the other when mirroring.
+    pub fn insert(&mut self, left: (&'t str, &'t str), right: (&'t str, &'t str)) {
+        self.swap.insert_type(left.0, right.0);
+        self.swap.insert_type(right.0, left.0);
+        self.swap.insert_recipe(left.1, right.1);
+        self.swap.insert_recipe(right.1, left.1);
+    }
+
+    pub fn with_pair(mut self, left: (&'t str, &'t str), right: (&'t str, &'t str)) -> Self {
+        self.insert(left, right);
+        self
+    }
+
+    pub fn get(&self, type_path: &str) -> Option<&'t str> {
+        self.swap.type_path(type_path)
+    }
+
+    /// Swap replacing every handed building and its recipe with its counterpart.
+    pub fn swap(&self) -> &RecipeSwap<'t> {
+        &self.swap
+    }
+}
+
+impl BlueprintBody {
+    /// Mirrors the blueprint across the plane through its centre that is normal to `axis`.
+    /// Buildings cannot have a negative scale, so each one is assumed to be symmetric across its
+    /// local Y axis and is turned to face its mirrored direction. Actor relative vectors such as
+    /// spline points are mirrored in the actor's frame to match.
+    /// Handed buildings are swapped for their counterpart in `table` with
+    /// [`BlueprintBody::swap_recipes`], which also replaces the recipe they were built with and
+    /// renames them after their new class. Returns the renamed instance names.
+    pub fn mirror(&mut self, axis: MirrorAxis, table: &MirrorTable) -> HashMap<String, String> {
+        let normal = axis.normal();
+
+        for header in &mut self.object_headers {
+            if let ObjectHeaderType::Actor(actor) = header {
+                actor.set_position(actor.position().mirror(normal));
+                actor.set_rotation(axis.mirror_rotation(actor.rotation()));
+            }
+        }
+
+        for object in &mut self.objects {
+            object
+                .properties_mut()
+                .for_each_struct_mut(&mut |name, data| match data {
+                    StructDataType::Vector(vector) => {
+                        if WORLD_POSITIONS.contains(&name) {
+                            *vector = vector.mirror(normal);
+                        } else if name != SCALE {
+                            *vector = vector.mirror(Vec3::Y);
+                        }
+                    }
+                    StructDataType::Quat(quat) => *quat = quat.mirror(Vec3::Y),
+                    _ => {}
+                });
+        }
+
+        self.swap_recipes(table.swap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    #[test]
+    fn check_mirror_rotation() {
+        // A building facing along the mirror axis turns around, one facing across it stays
+        let facing_x = MirrorAxis::X.mirror_rotation(Quat::IDENTITY);
+        assert!(facing_x.abs_diff_eq(Quat::from_yaw(180.0), 1e-9));
+
+        let facing_y = MirrorAxis::X.mirror_rotation(Quat::from_yaw(90.0));
+        assert!(facing_y.abs_diff_eq(Quat::from_yaw(90.0), 1e-9));
+
+        let mirrored = MirrorAxis::Y.mirror_rotation(Quat::from_yaw(30.0));
+        assert!(mirrored.abs_diff_eq(Quat::from_yaw(-30.0), 1e-9));
+    }
+
+    #[test]
+    fn check_mirror_table() {
+        const LEFT: (&str, &str) = ("/Game/Left.Left_C\0", "/Game/Recipe_Left.Recipe_Left_C\0");
+        const RIGHT: (&str, &str) = (
+            "/Game/Right.Right_C\0",
+            "/Game/Recipe_Right.Recipe_Right_C\0",
+        );
+
+        let table = MirrorTable::new().with_pair(LEFT, RIGHT);
+        assert_eq!(table.get(LEFT.0), Some(RIGHT.0));
+        assert_eq!(table.get(RIGHT.0), Some(LEFT.0));
+        assert_eq!(table.get("/Game/Other.Other_C\0"), None);
+        assert_eq!(table.swap().recipe(LEFT.1), Some(RIGHT.1));
+        assert_eq!(table.swap().recipe(RIGHT.1), Some(LEFT.1));
+    }
+
+    #[test]
+    fn check_mirror_twice() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+        const GENERATOR: (&str, &str) = (
+            "/Game/FactoryGame/Buildable/Factory/GeneratorCoal/Build_GeneratorCoal.Build_GeneratorCoal_C\0",
+            "/Game/FactoryGame/Recipes/Buildings/Recipe_GeneratorCoal.Recipe_GeneratorCoal_C\0",
+        );
+        const MIRRORED: (&str, &str) = (
+            "/Game/FactoryGame/Buildable/Mirrored/Build_Mirrored.Build_Mirrored_C\0",
+            "/Game/Recipe_Mirrored.Recipe_Mirrored_C\0",
+        );
+
+        let original = Blueprint::new(DATA).expect("Parse should succeed");
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let table = MirrorTable::new().with_pair(GENERATOR, MIRRORED);
+
+        let renames = blueprint.mirror(MirrorAxis::X, &table);
+        assert_eq!(renames.len(), 3);
+        for (old, new) in &renames {
+            assert_eq!(
+                new,
+                &old.replace("Build_GeneratorCoal_C_", "Build_Mirrored_C_")
+            );
+        }
+
+        let ObjectHeaderType::Actor(generator) = &blueprint.body.object_headers[0] else {
+            panic!("Expected an actor header");
+        };
+        assert_eq!(generator.type_path, MIRRORED.0);
+        assert!(generator.instance_name.contains(".Build_Mirrored_C_"));
+        assert!(
+            generator
+                .position()
+                .abs_diff_eq(Vec3::new(1000.0, -200.0, 0.0), 1e-3)
+        );
+
+        // Mirrored generators are built with the mirrored recipe, which the header lists instead
+        let built_with = |blueprint: &Blueprint, recipe| {
+            blueprint
+                .body
+                .query()
+                .reference("mBuiltWithRecipe", recipe)
+                .iter()
+                .count()
+        };
+        assert_eq!(built_with(&blueprint, GENERATOR.1), 0);
+        assert_eq!(built_with(&blueprint, MIRRORED.1), 3);
+        let recipes = &blueprint.header.recipie_list.recipies;
+        assert!(recipes.iter().any(|recipe| recipe.0 == MIRRORED.1));
+        assert!(!recipes.iter().any(|recipe| recipe.0 == GENERATOR.1));
+        assert_eq!(blueprint.body.validate_names(), []);
+
+        // Mirroring back restores every actor
+        blueprint.mirror(MirrorAxis::X, &table);
+        assert_eq!(
+            blueprint.header.recipie_list.recipies,
+            original.header.recipie_list.recipies
+        );
+        assert_eq!(built_with(&blueprint, GENERATOR.1), 3);
+        for (header, original) in blueprint
+            .body
+            .object_headers
+            .iter()
+            .zip(&original.body.object_headers)
+        {
+            if let (ObjectHeaderType::Actor(actor), ObjectHeaderType::Actor(original)) =
+                (header, original)
+            {
+                assert_eq!(actor.type_path, original.type_path);
+                assert_eq!(actor.instance_name, original.instance_name);
+                assert!(actor.transform().abs_diff_eq(&original.transform(), 1e-2));
+            }
+        }
+    }
+}
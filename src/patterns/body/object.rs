@@ -1,76 +1,218 @@
 use std::io::Write;
 
-use winnow::{Bytes, Parser, binary::le_u32, combinator::seq, error::StrContext};
+use winnow::{
+    Bytes, Parser,
+    binary::{le_u32, length_and_then},
+    combinator::{alt, eof, repeat, seq},
+    error::StrContext,
+    token::rest,
+};
 
 use crate::{
     bp_write::BPWrite,
     patterns::body::{
-        ObjectRef, PropertyList, object_ref::object_ref, property_list::property_list,
+        BUILDABLE_SUBSYSTEM, ObjectRef, PropertyList, object_ref::object_ref,
+        property_list::property_list,
     },
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ObjectType {
     Actor(ActorObject),
-    Component,
+    Component(ComponentObject),
 }
 
 impl ObjectType {
     pub fn size(&self) -> u32 {
         match self {
             ObjectType::Actor(actor) => actor.size(),
-            ObjectType::Component => unimplemented!(),
+            ObjectType::Component(component) => component.size(),
         }
     }
+
+    pub fn properties(&self) -> &PropertyList {
+        match self {
+            ObjectType::Actor(actor) => &actor.properties,
+            ObjectType::Component(component) => &component.properties,
+        }
+    }
+
+    pub fn properties_mut(&mut self) -> &mut PropertyList {
+        match self {
+            ObjectType::Actor(actor) => &mut actor.properties,
+            ObjectType::Component(component) => &mut component.properties,
+        }
+    }
+
+    /// Calls `f` with the path name of every object referenced by this object.
+    pub fn for_each_path_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        if let ObjectType::Actor(actor) = self {
+            f(&mut actor.parent_object.path_name);
+            for component in &mut actor.components {
+                f(&mut component.path_name);
+            }
+            if let ExtraData::Wire { source, target } = &mut actor.extra_data {
+                f(&mut source.path_name);
+                f(&mut target.path_name);
+            }
+        }
+
+        self.properties_mut().for_each_path_name_mut(f);
+    }
 }
 
 impl<W: Write> BPWrite<W> for &ObjectType {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
         match self {
             ObjectType::Actor(actor) => actor.bp_write(writer),
-            ObjectType::Component => unimplemented!(),
+            ObjectType::Component(component) => component.bp_write(writer),
+        }
+    }
+}
+
+/// Class specific data following the properties of an actor
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtraData {
+    /// Power connection components at both ends of a power line
+    Wire {
+        source: ObjectRef,
+        target: ObjectRef,
+    },
+    /// Data that is not understood, kept as is
+    Raw(Vec<u8>),
+}
+
+impl ExtraData {
+    pub fn size(&self) -> u32 {
+        match self {
+            ExtraData::Wire { source, target } => source.size() + target.size(),
+            ExtraData::Raw(data) => data.len() as u32,
+        }
+    }
+}
+
+impl<W: Write> BPWrite<W> for &ExtraData {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        match self {
+            ExtraData::Wire { source, target } => {
+                source.bp_write(writer)?;
+                target.bp_write(writer)
+            }
+            ExtraData::Raw(data) => data.as_slice().bp_write(writer),
         }
     }
 }
 
+pub fn extra_data(data: &mut &Bytes) -> winnow::Result<ExtraData> {
+    alt((
+        seq! {ExtraData::Wire {
+            source: object_ref.context(StrContext::Label("wire source")),
+            target: object_ref.context(StrContext::Label("wire target")),
+            _: eof,
+        }},
+        rest.map(|data: &[u8]| ExtraData::Raw(data.to_vec())),
+    ))
+    .parse_next(data)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ActorObject {
-    parent_object: ObjectRef,
-    // TODO Components
-    properties: PropertyList,
+    pub parent_object: ObjectRef,
+    pub components: Vec<ObjectRef>,
+    pub properties: PropertyList,
+    pub extra_data: ExtraData,
 }
 
 impl ActorObject {
+    /// A building with `properties`, owned by the buildable subsystem. Its components are left
+    /// for the caller to add.
+    pub(crate) fn buildable(properties: PropertyList) -> Self {
+        ActorObject {
+            parent_object: ObjectRef::level(BUILDABLE_SUBSYSTEM),
+            components: Vec::new(),
+            properties,
+            extra_data: ExtraData::Raw(Vec::new()),
+        }
+    }
+
     pub fn size(&self) -> u32 {
         let parent_object_size = self.parent_object.size();
+        let components_size = self.components.iter().map(ObjectRef::size).sum::<u32>();
         let properties_size = self.properties.size();
+        let extra_data_size = self.extra_data.size();
 
-        parent_object_size + properties_size + 12
+        parent_object_size + components_size + properties_size + extra_data_size + 12
     }
 }
 
 impl<W: Write> BPWrite<W> for &ActorObject {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
-        let parent_size = self.parent_object.size();
-        let prop_size = self.properties.size();
-        let size = dbg!(parent_size) + dbg!(prop_size) + 8;
+        // Size does not include itself
+        let size = self.size() - 4;
+        let component_count: u32 = self
+            .components
+            .len()
+            .try_into()
+            .expect("Too many components");
 
         size.bp_write(writer)?;
         self.parent_object.bp_write(writer)?;
-        0u32.bp_write(writer)?;
+        component_count.bp_write(writer)?;
+        self.components.bp_write(writer)?;
+        self.properties.bp_write(writer)?;
+        [0u8; 4].bp_write(writer)?;
+        self.extra_data.bp_write(writer)
+    }
+}
+
+pub fn actor_object(data: &mut &Bytes) -> winnow::Result<ActorObject> {
+    length_and_then(
+        le_u32.context(StrContext::Label("size")),
+        seq! {ActorObject {
+            parent_object: object_ref.context(StrContext::Label("parent object")),
+            components: le_u32.context(StrContext::Label("component count"))
+                .flat_map(|count| repeat(count as usize, object_ref))
+                .context(StrContext::Label("components")),
+            properties: property_list,
+            _: &[0u8; 4],
+            extra_data: extra_data.context(StrContext::Label("extra data")),
+        }},
+    )
+    .parse_next(data)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentObject {
+    pub properties: PropertyList,
+    pub extra_data: Vec<u8>,
+}
+
+impl ComponentObject {
+    pub fn size(&self) -> u32 {
+        self.properties.size() + self.extra_data.len() as u32 + 8
+    }
+}
+
+impl<W: Write> BPWrite<W> for &ComponentObject {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        let size = self.size() - 4;
+
+        size.bp_write(writer)?;
         self.properties.bp_write(writer)?;
-        [0u8; 4].bp_write(writer)
+        [0u8; 4].bp_write(writer)?;
+        self.extra_data.as_slice().bp_write(writer)
     }
 }
 
-pub fn actor_object<'d>(data: &mut &'d Bytes) -> winnow::Result<ActorObject> {
-    seq! {ActorObject {
-        _: le_u32.context(StrContext::Label("size")),
-        parent_object: object_ref,
-        _: le_u32.context(StrContext::Label("component count")),
-        properties: property_list,
-        _: &[0u8; 4],
-    }}
+pub fn component_object(data: &mut &Bytes) -> winnow::Result<ComponentObject> {
+    length_and_then(
+        le_u32.context(StrContext::Label("size")),
+        seq! {ComponentObject {
+            properties: property_list,
+            _: &[0u8; 4],
+            extra_data: rest.map(<[u8]>::to_vec).context(StrContext::Label("extra data")),
+        }},
+    )
     .parse_next(data)
 }
 
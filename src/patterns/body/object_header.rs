@@ -1,6 +1,8 @@
 mod actor_header;
+mod component_header;
 
 pub use actor_header::*;
+pub use component_header::*;
 use std::io::{Error, Write};
 
 use crate::bp_write::BPWrite;
@@ -12,23 +14,52 @@ use winnow::{
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ObjectHeaderType {
-    Component,
+    Component(ComponentHeader),
     Actor(ActorHeader),
 }
 
 impl ObjectHeaderType {
     pub fn size(&self) -> u32 {
         match self {
-            ObjectHeaderType::Component => unimplemented!(),
+            ObjectHeaderType::Component(component_header) => component_header.size() + 4,
             ObjectHeaderType::Actor(actor_header) => actor_header.size() + 4,
         }
     }
+
+    pub fn instance_name(&self) -> &str {
+        match self {
+            ObjectHeaderType::Component(component_header) => &component_header.instance_name,
+            ObjectHeaderType::Actor(actor_header) => &actor_header.instance_name,
+        }
+    }
+
+    /// Class of the object, the type path of actors and the class name of components.
+    pub fn type_path(&self) -> &str {
+        match self {
+            ObjectHeaderType::Component(component_header) => &component_header.class_name,
+            ObjectHeaderType::Actor(actor_header) => &actor_header.type_path,
+        }
+    }
+
+    /// Calls `f` with the instance name of the object and, for components, the name of its actor.
+    pub fn for_each_path_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            ObjectHeaderType::Component(component_header) => {
+                f(&mut component_header.instance_name);
+                f(&mut component_header.parent_actor);
+            }
+            ObjectHeaderType::Actor(actor_header) => f(&mut actor_header.instance_name),
+        }
+    }
 }
 
 impl<W: Write> BPWrite<W> for &ObjectHeaderType {
     fn bp_write(self, writer: &mut W) -> Result<(), Error> {
         match self {
-            ObjectHeaderType::Component => unimplemented!(),
+            ObjectHeaderType::Component(header) => {
+                0u32.bp_write(writer)?;
+                header.bp_write(writer)
+            }
             ObjectHeaderType::Actor(header) => {
                 1u32.bp_write(writer)?;
                 header.bp_write(writer)
@@ -39,14 +70,23 @@ impl<W: Write> BPWrite<W> for &ObjectHeaderType {
 
 pub fn object_header_type(data: &mut &Bytes) -> winnow::Result<ObjectHeaderType> {
     alt((
-        (&0_u32.to_le_bytes()[..]).map(|_| ObjectHeaderType::Component),
+        component_type,
         actor_type,
         fail.context(StrContext::Label("unknown header type")),
     ))
     .parse_next(data)
 }
 
-fn actor_type<'d>(data: &mut &'d Bytes) -> winnow::Result<ObjectHeaderType> {
+fn component_type(data: &mut &Bytes) -> winnow::Result<ObjectHeaderType> {
+    preceded(
+        (&0_u32.to_le_bytes()).context(StrContext::Label("object header type")),
+        component_header.context(StrContext::Label("component header")),
+    )
+    .map(ObjectHeaderType::Component)
+    .parse_next(data)
+}
+
+fn actor_type(data: &mut &Bytes) -> winnow::Result<ObjectHeaderType> {
     preceded(
         (&1_u32.to_le_bytes()).context(StrContext::Label("object header type")),
         actor_header.context(StrContext::Label("actor header")),
@@ -59,7 +99,6 @@ fn actor_type<'d>(data: &mut &'d Bytes) -> winnow::Result<ObjectHeaderType> {
 mod tests {
     use super::*;
 
-    // TODO Check component and fail when relevent
     #[test]
     fn check_object_header_type() {
         const DATA: [u8; 0xE6] = [
@@ -9,7 +9,11 @@ use winnow::{
 
 use crate::{
     bp_write::BPWrite,
-    patterns::factory_string::{FStringExt, fstring},
+    patterns::{
+        body::LEVEL_NAME,
+        factory_string::{FStringExt, fstring},
+    },
+    transform::{Quat, Transform, Vec3},
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +35,28 @@ pub struct ActorHeader {
 }
 
 impl ActorHeader {
+    /// Header of a new actor of class `type_path` in the persistent level.
+    pub fn new(type_path: &str, instance_name: String, transform: &Transform) -> Self {
+        let mut header = ActorHeader {
+            type_path: type_path.to_owned(),
+            root_object: LEVEL_NAME.to_owned(),
+            instance_name,
+            unknown: 1,
+            rotation_x: 0.0,
+            rotation_y: 0.0,
+            rotation_z: 0.0,
+            rotation_w: 1.0,
+            position_x: 0.0,
+            position_y: 0.0,
+            position_z: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            scale_z: 1.0,
+        };
+        header.set_transform(transform);
+        header
+    }
+
     pub fn size(&self) -> u32 {
         let type_path_size = self.type_path.size();
         let root_object_size = self.root_object.size();
@@ -38,6 +64,60 @@ impl ActorHeader {
 
         type_path_size + root_object_size + instance_name_size + 48
     }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::new(
+            self.rotation_x.into(),
+            self.rotation_y.into(),
+            self.rotation_z.into(),
+            self.rotation_w.into(),
+        )
+    }
+
+    pub fn set_rotation(&mut self, rotation: Quat) {
+        self.rotation_x = rotation.x as f32;
+        self.rotation_y = rotation.y as f32;
+        self.rotation_z = rotation.z as f32;
+        self.rotation_w = rotation.w as f32;
+    }
+
+    pub fn position(&self) -> Vec3 {
+        Vec3::new(
+            self.position_x.into(),
+            self.position_y.into(),
+            self.position_z.into(),
+        )
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position_x = position.x as f32;
+        self.position_y = position.y as f32;
+        self.position_z = position.z as f32;
+    }
+
+    pub fn scale(&self) -> Vec3 {
+        Vec3::new(
+            self.scale_x.into(),
+            self.scale_y.into(),
+            self.scale_z.into(),
+        )
+    }
+
+    pub fn set_scale(&mut self, scale: Vec3) {
+        self.scale_x = scale.x as f32;
+        self.scale_y = scale.y as f32;
+        self.scale_z = scale.z as f32;
+    }
+
+    pub fn transform(&self) -> Transform {
+        Transform::new(self.rotation(), self.position(), self.scale())
+    }
+
+    pub fn set_transform(&mut self, transform: &Transform) {
+        self.set_rotation(transform.rotation);
+        self.set_position(transform.translation);
+        self.set_scale(transform.scale);
+    }
 }
 
 impl<W: Write> BPWrite<W> for &ActorHeader {
@@ -61,7 +141,7 @@ impl<W: Write> BPWrite<W> for &ActorHeader {
     }
 }
 
-pub fn actor_header<'d>(data: &mut &'d Bytes) -> winnow::Result<ActorHeader> {
+pub fn actor_header(data: &mut &Bytes) -> winnow::Result<ActorHeader> {
     seq! { ActorHeader {
         type_path: fstring.context(StrContext::Label("type path")).map(ToOwned::to_owned),
         root_object: fstring.context(StrContext::Label("root_object")).map(ToOwned::to_owned),
@@ -143,5 +223,14 @@ mod tests {
             .expect("Write should succeed");
 
         assert_eq!(buf, DATA);
+
+        let transform = actor_header.transform();
+        assert!(transform.rotation.abs_diff_eq(Quat::from_yaw(-90.0), 1e-6));
+        assert_eq!(transform.translation, Vec3::new(200.0, -1200.0, 200.0));
+        assert_eq!(transform.scale, Vec3::ONE);
+
+        let mut moved = actor_header.clone();
+        moved.set_transform(&transform);
+        assert_eq!(moved, actor_header);
     }
 }
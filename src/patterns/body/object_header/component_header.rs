@@ -0,0 +1,95 @@
+use std::io::Write;
+
+use winnow::{Bytes, Parser, combinator::seq, error::StrContext};
+
+use crate::{
+    bp_write::BPWrite,
+    patterns::factory_string::{FStringExt, fstring},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentHeader {
+    pub class_name: String,
+    pub root_object: String,
+    pub instance_name: String,
+    pub parent_actor: String,
+}
+
+impl ComponentHeader {
+    pub fn size(&self) -> u32 {
+        let class_name_size = self.class_name.size();
+        let root_object_size = self.root_object.size();
+        let instance_name_size = self.instance_name.size();
+        let parent_actor_size = self.parent_actor.size();
+
+        class_name_size + root_object_size + instance_name_size + parent_actor_size
+    }
+}
+
+impl<W: Write> BPWrite<W> for &ComponentHeader {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.class_name.bp_write(writer)?;
+        self.root_object.bp_write(writer)?;
+        self.instance_name.bp_write(writer)?;
+        self.parent_actor.bp_write(writer)
+    }
+}
+
+pub fn component_header(data: &mut &Bytes) -> winnow::Result<ComponentHeader> {
+    seq! { ComponentHeader {
+        class_name: fstring.context(StrContext::Label("class name")).map(ToOwned::to_owned),
+        root_object: fstring.context(StrContext::Label("root object")).map(ToOwned::to_owned),
+        instance_name: fstring.context(StrContext::Label("instance name")).map(ToOwned::to_owned),
+        parent_actor: fstring.context(StrContext::Label("parent actor")).map(ToOwned::to_owned),
+    }}
+    .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_component_header() {
+        const DATA: [u8; 0xE4] = [
+            0x2F, 0x00, 0x00, 0x00, 0x2F, 0x53, 0x63, 0x72, 0x69, 0x70, 0x74, 0x2F, 0x46, 0x61,
+            0x63, 0x74, 0x6F, 0x72, 0x79, 0x47, 0x61, 0x6D, 0x65, 0x2E, 0x46, 0x47, 0x50, 0x6F,
+            0x77, 0x65, 0x72, 0x43, 0x6F, 0x6E, 0x6E, 0x65, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x43,
+            0x6F, 0x6D, 0x70, 0x6F, 0x6E, 0x65, 0x6E, 0x74, 0x00, 0x11, 0x00, 0x00, 0x00, 0x50,
+            0x65, 0x72, 0x73, 0x69, 0x73, 0x74, 0x65, 0x6E, 0x74, 0x5F, 0x4C, 0x65, 0x76, 0x65,
+            0x6C, 0x00, 0x52, 0x00, 0x00, 0x00, 0x50, 0x65, 0x72, 0x73, 0x69, 0x73, 0x74, 0x65,
+            0x6E, 0x74, 0x5F, 0x4C, 0x65, 0x76, 0x65, 0x6C, 0x3A, 0x50, 0x65, 0x72, 0x73, 0x69,
+            0x73, 0x74, 0x65, 0x6E, 0x74, 0x4C, 0x65, 0x76, 0x65, 0x6C, 0x2E, 0x42, 0x75, 0x69,
+            0x6C, 0x64, 0x5F, 0x47, 0x65, 0x6E, 0x65, 0x72, 0x61, 0x74, 0x6F, 0x72, 0x43, 0x6F,
+            0x61, 0x6C, 0x5F, 0x43, 0x5F, 0x32, 0x31, 0x34, 0x37, 0x34, 0x36, 0x38, 0x34, 0x34,
+            0x35, 0x2E, 0x50, 0x6F, 0x77, 0x65, 0x72, 0x43, 0x6F, 0x6E, 0x6E, 0x65, 0x63, 0x74,
+            0x69, 0x6F, 0x6E, 0x00, 0x42, 0x00, 0x00, 0x00, 0x50, 0x65, 0x72, 0x73, 0x69, 0x73,
+            0x74, 0x65, 0x6E, 0x74, 0x5F, 0x4C, 0x65, 0x76, 0x65, 0x6C, 0x3A, 0x50, 0x65, 0x72,
+            0x73, 0x69, 0x73, 0x74, 0x65, 0x6E, 0x74, 0x4C, 0x65, 0x76, 0x65, 0x6C, 0x2E, 0x42,
+            0x75, 0x69, 0x6C, 0x64, 0x5F, 0x47, 0x65, 0x6E, 0x65, 0x72, 0x61, 0x74, 0x6F, 0x72,
+            0x43, 0x6F, 0x61, 0x6C, 0x5F, 0x43, 0x5F, 0x32, 0x31, 0x34, 0x37, 0x34, 0x36, 0x38,
+            0x34, 0x34, 0x35, 0x00,
+        ];
+
+        let correct = ComponentHeader {
+            class_name: "/Script/FactoryGame.FGPowerConnectionComponent\0".to_owned(),
+            root_object: "Persistent_Level\0".to_owned(),
+            instance_name: "Persistent_Level:PersistentLevel.Build_GeneratorCoal_C_2147468445.PowerConnection\0".to_owned(),
+            parent_actor: "Persistent_Level:PersistentLevel.Build_GeneratorCoal_C_2147468445\0".to_owned(),
+        };
+
+        let component_header = component_header
+            .parse((&DATA[..]).into())
+            .expect("Parse should succeed");
+
+        assert_eq!(component_header, correct);
+        assert_eq!(component_header.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        component_header
+            .bp_write(&mut buf)
+            .expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+}
@@ -4,7 +4,10 @@ use winnow::{Bytes, Parser, combinator::seq, error::StrContext};
 
 use crate::{
     bp_write::BPWrite,
-    patterns::factory_string::{FStringExt, fstring},
+    patterns::{
+        body::LEVEL_NAME,
+        factory_string::{FStringExt, fstring},
+    },
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -14,6 +17,14 @@ pub struct ObjectRef {
 }
 
 impl ObjectRef {
+    /// Reference to the object at `path_name` in the blueprint level.
+    pub(crate) fn level(path_name: &str) -> Self {
+        ObjectRef {
+            level_name: LEVEL_NAME.to_owned(),
+            path_name: path_name.to_owned(),
+        }
+    }
+
     pub fn size(&self) -> u32 {
         self.level_name.size() + self.path_name.size()
     }
@@ -26,7 +37,7 @@ impl<W: Write> BPWrite<W> for &ObjectRef {
     }
 }
 
-pub fn object_ref<'d>(data: &mut &'d Bytes) -> winnow::Result<ObjectRef> {
+pub fn object_ref(data: &mut &Bytes) -> winnow::Result<ObjectRef> {
     seq! {ObjectRef {
         level_name: fstring.context(StrContext::Label("level name")).map(ToOwned::to_owned),
         path_name: fstring.context(StrContext::Label("path name")).map(ToOwned::to_owned),
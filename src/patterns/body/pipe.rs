@@ -0,0 +1,338 @@
+use std::fmt;
+
+use color_eyre::Result;
+
+use crate::patterns::body::{
+    ActorObject, BlueprintBody, CONNECTED_COMPONENT, ConnectionKind, FluidBox, ObjectHeaderType,
+    ObjectType, Spline,
+};
+
+/// Fluid held by a pipe or a fluid buffer
+pub const FLUID_BOX: &str = "mFluidBox";
+/// Pipe network of a pipe connection in the world the blueprint was saved from
+pub const PIPE_NETWORK_ID: &str = "mPipeNetworkID";
+
+/// A pipe connection component and the component it is attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeConnection {
+    /// Instance name of the connection component
+    pub component: String,
+    /// Instance name of the actor owning the component
+    pub actor: String,
+    /// Instance name of the attached component
+    pub connected: Option<String>,
+    pub network_id: Option<i32>,
+}
+
+/// A pipeline segment, an actor with a spline running between pipe connections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipe {
+    pub instance_name: String,
+    pub type_path: String,
+    /// Spline in blueprint space
+    pub spline: Spline,
+    pub fluid_box: Option<FluidBox>,
+    /// Connections at the ends of the pipe
+    pub connections: Vec<PipeConnection>,
+}
+
+/// Problem with the pipes of a blueprint, see [`BlueprintBody::validate_pipes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipeError {
+    /// An end of a pipe is not attached to anything
+    Unconnected { pipe: String, component: String },
+    /// A pipe connection is attached to a component that is not in the body or that does not
+    /// connect back
+    Dangling { component: String, target: String },
+    /// Two attached pipe connections are in different pipe networks
+    NetworkMismatch { component: String, target: String },
+}
+
+impl fmt::Display for PipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipeError::Unconnected { pipe, component } => {
+                write!(f, "pipe {pipe:?} has an unconnected end at {component:?}")
+            }
+            PipeError::Dangling { component, target } => write!(
+                f,
+                "pipe connection {component:?} is attached to {target:?} which does not connect back"
+            ),
+            PipeError::NetworkMismatch { component, target } => write!(
+                f,
+                "pipe connections {component:?} and {target:?} are in different pipe networks"
+            ),
+        }
+    }
+}
+
+impl ActorObject {
+    pub fn fluid_box(&self) -> Option<FluidBox> {
+        self.get(FLUID_BOX)
+    }
+
+    /// Replaces the fluid held by the actor, adding a fluid box if it has none.
+    pub fn set_fluid_box(&mut self, fluid_box: FluidBox) -> Result<()> {
+        if self.properties.find(FLUID_BOX).is_some() {
+            self.set(FLUID_BOX, fluid_box)
+        } else {
+            self.insert(FLUID_BOX, fluid_box)
+        }
+    }
+}
+
+impl BlueprintBody {
+    /// Every pipe connection component, of pipes as well as of junctions, pumps and buildings.
+    pub fn pipe_connections(&self) -> Vec<PipeConnection> {
+        self.entities()
+            .filter_map(|entity| {
+                let (ObjectHeaderType::Component(header), ObjectType::Component(component)) =
+                    (entity.header, entity.object)
+                else {
+                    return None;
+                };
+                if ConnectionKind::from_class(&header.class_name) != Some(ConnectionKind::Pipe) {
+                    return None;
+                }
+
+                let properties = &component.properties;
+                Some(PipeConnection {
+                    component: header.instance_name.clone(),
+                    actor: header.parent_actor.clone(),
+                    connected: properties
+                        .get::<&str>(CONNECTED_COMPONENT)
+                        .filter(|target| !target.trim_end_matches('\0').is_empty())
+                        .map(ToOwned::to_owned),
+                    network_id: properties.get(PIPE_NETWORK_ID),
+                })
+            })
+            .collect()
+    }
+
+    /// Pipe connections attached to each other, each pair listed once. See
+    /// [`BlueprintBody::connections`].
+    pub fn pipe_pairs(&self) -> Vec<(String, String)> {
+        self.connections()
+            .edges
+            .into_iter()
+            .filter(|edge| edge.kind == ConnectionKind::Pipe)
+            .map(|edge| (edge.from_component, edge.to_component))
+            .collect()
+    }
+
+    /// Every pipeline segment with its spline, fluid and end connections.
+    pub fn pipes(&self) -> Vec<Pipe> {
+        let connections = self.pipe_connections();
+
+        self.entities()
+            .filter_map(|entity| {
+                let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                    (entity.header, entity.object)
+                else {
+                    return None;
+                };
+                let ends: Vec<_> = connections
+                    .iter()
+                    .filter(|connection| connection.actor == header.instance_name)
+                    .cloned()
+                    .collect();
+                if ends.is_empty() {
+                    return None;
+                }
+
+                Some(Pipe {
+                    instance_name: header.instance_name.clone(),
+                    type_path: header.type_path.clone(),
+                    spline: entity.world_spline()?,
+                    fluid_box: actor.fluid_box(),
+                    connections: ends,
+                })
+            })
+            .collect()
+    }
+
+    /// Finds pipe ends that are not attached to anything, connections to components that are
+    /// missing or do not connect back, and attached connections in different pipe networks.
+    pub fn validate_pipes(&self) -> Vec<PipeError> {
+        let connections = self.pipe_connections();
+        let mut errors = Vec::new();
+
+        for pipe in self.pipes() {
+            for end in pipe.connections {
+                if end.connected.is_none() {
+                    errors.push(PipeError::Unconnected {
+                        pipe: pipe.instance_name.clone(),
+                        component: end.component,
+                    });
+                }
+            }
+        }
+
+        for connection in &connections {
+            let Some(target) = &connection.connected else {
+                continue;
+            };
+            match connections.iter().find(|other| other.component == *target) {
+                Some(other) if other.connected.as_ref() == Some(&connection.component) => {
+                    if let (Some(id), Some(other_id)) = (connection.network_id, other.network_id)
+                        && id != other_id
+                        && connection.component < other.component
+                    {
+                        errors.push(PipeError::NetworkMismatch {
+                            component: connection.component.clone(),
+                            target: target.clone(),
+                        });
+                    }
+                }
+                _ => errors.push(PipeError::Dangling {
+                    component: connection.component.clone(),
+                    target: target.clone(),
+                }),
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{Blueprint, body::blueprint_body},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    #[test]
+    fn check_pipes() {
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let pipes = body.pipes();
+
+        assert_eq!(pipes.len(), 5);
+        for pipe in &pipes {
+            assert!(pipe.type_path.contains("Build_Pipeline_C"));
+            assert_eq!(pipe.connections.len(), 2);
+            assert!(pipe.spline.length() > 0.0);
+            assert_eq!(pipe.fluid_box, None);
+        }
+
+        // Pipes, junctions and generators connect through 25 pipe connections
+        assert_eq!(body.pipe_connections().len(), 25);
+        let pairs = body.pipe_pairs();
+        assert_eq!(
+            pairs.len(),
+            body.pipe_connections()
+                .iter()
+                .filter(|connection| connection.connected.is_some())
+                .count()
+                / 2
+        );
+        assert_eq!(body.validate_pipes(), []);
+    }
+
+    #[test]
+    fn check_fluid_box() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let name = body.pipes()[0].instance_name.clone();
+
+        let ObjectType::Actor(actor) = body.entity_mut(&name).expect("Pipe should exist").object
+        else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .set_fluid_box(FluidBox { content: 0.5 })
+            .expect("Fluid box should be added");
+        actor
+            .set_fluid_box(FluidBox { content: 1.25 })
+            .expect("Fluid box should be replaced");
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+        assert_eq!(
+            parsed.pipes()[0].fluid_box,
+            Some(FluidBox { content: 1.25 })
+        );
+    }
+
+    #[test]
+    fn check_unconnected_pipes() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let pipe = body.pipes().remove(0);
+        let end = &pipe.connections[0];
+        let target = end.connected.clone().expect("Pipe end should be connected");
+
+        let ObjectType::Component(component) = body
+            .entity_mut(&end.component)
+            .expect("Connection should exist")
+            .object
+        else {
+            panic!("Expected a component object");
+        };
+        component.properties.remove(CONNECTED_COMPONENT);
+
+        assert_eq!(
+            body.validate_pipes(),
+            [
+                PipeError::Unconnected {
+                    pipe: pipe.instance_name.clone(),
+                    component: end.component.clone(),
+                },
+                PipeError::Dangling {
+                    component: target,
+                    target: end.component.clone(),
+                },
+            ]
+        );
+
+        // Connections to a removed pipe no longer connect back
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        body.remove(&pipe.instance_name);
+        let errors = body.validate_pipes();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|error| matches!(
+            error,
+            PipeError::Dangling { target, .. } if target.starts_with(pipe.instance_name.trim_end_matches('\0'))
+        )));
+    }
+
+    #[test]
+    fn check_network_mismatch() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let (from, to) = body.pipe_pairs().remove(0);
+
+        for component in [&from, &to] {
+            let ObjectType::Component(component) = body
+                .entity_mut(component)
+                .expect("Connection should exist")
+                .object
+            else {
+                panic!("Expected a component object");
+            };
+            component
+                .properties
+                .insert(PIPE_NETWORK_ID, 1)
+                .expect("Insert should succeed");
+        }
+        assert_eq!(body.validate_pipes(), []);
+
+        let ObjectType::Component(component) = body
+            .entity_mut(&to)
+            .expect("Connection should exist")
+            .object
+        else {
+            panic!("Expected a component object");
+        };
+        component
+            .properties
+            .set(PIPE_NETWORK_ID, 2)
+            .expect("Set should succeed");
+        assert_eq!(body.validate_pipes().len(), 1);
+    }
+}
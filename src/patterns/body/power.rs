@@ -0,0 +1,542 @@
+use std::{collections::HashMap, fmt};
+
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+
+use crate::{
+    patterns::body::{
+        ActorHeader, ActorObject, ArrayValue, BlueprintBody, CACHED_LENGTH, ConnectionKind,
+        CustomizationData, ExtraData, ObjectHeaderType, ObjectRef, ObjectType, PropertyList,
+        StructDataType, class_name,
+    },
+    transform::{Transform, Vec3},
+};
+
+/// Type path of the power lines joining power connections
+pub const POWER_LINE: &str =
+    "/Game/FactoryGame/Buildable/Factory/PowerLine/Build_PowerLine.Build_PowerLine_C\0";
+pub const POWER_LINE_RECIPE: &str =
+    "/Game/FactoryGame/Recipes/Buildings/Recipe_PowerLine.Recipe_PowerLine_C\0";
+/// Power lines attached to a power connection component
+pub const WIRES: &str = "mWires";
+
+const WIRE_INSTANCES: &str = "mWireInstances";
+const WIRE_INSTANCE: &str = "WireInstance\0";
+/// Ends of the wire in the world the blueprint was saved from
+const LOCATIONS: &str = "Locations";
+/// Ends of the wire relative to the power line actor, which sits at its second end
+const CACHED_RELATIVE_LOCATIONS: &str = "CachedRelativeLocations";
+const BUILT_WITH_RECIPE: &str = "mBuiltWithRecipe";
+
+/// A power line between two power connection components.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wire {
+    pub instance_name: String,
+    /// Instance name of the power connection at the start of the wire
+    pub source: String,
+    /// Instance name of the power connection at the end of the wire
+    pub target: String,
+    /// Start of the wire in blueprint space
+    pub start: Vec3,
+    /// End of the wire in blueprint space
+    pub end: Vec3,
+}
+
+impl Wire {
+    /// Length of the wire in centimetres.
+    pub fn length(&self) -> f64 {
+        self.start.distance(self.end)
+    }
+}
+
+/// Problem with the power network of a blueprint, see [`BlueprintBody::validate_power`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowerError {
+    /// A power connection has more wires than its building allows
+    TooManyWires {
+        component: String,
+        wires: usize,
+        max: usize,
+    },
+    /// A wire ends at a power connection that is not in the body
+    MissingConnection { wire: String, component: String },
+}
+
+impl fmt::Display for PowerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerError::TooManyWires {
+                component,
+                wires,
+                max,
+            } => write!(
+                f,
+                "power connection {component:?} has {wires} wires but allows {max}"
+            ),
+            PowerError::MissingConnection { wire, component } => write!(
+                f,
+                "wire {wire:?} ends at power connection {component:?} which is not in the body"
+            ),
+        }
+    }
+}
+
+/// Number of wires the power connection of a building of type `type_path` takes, `None` when it
+/// is not a power pole and the game does not limit it in a way known here.
+pub fn max_wires(type_path: &str) -> Option<usize> {
+    let class = class_name(type_path);
+    if !class.starts_with("Build_PowerPole") {
+        return None;
+    }
+
+    Some(if class.contains("Mk3") {
+        10
+    } else if class.contains("Mk2") {
+        7
+    } else {
+        4
+    })
+}
+
+/// Number of wires attached to each power connection, by component instance name.
+fn wire_counts(wires: &[Wire]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for wire in wires {
+        for component in [&wire.source, &wire.target] {
+            *counts.entry(component.as_str()).or_default() += 1;
+        }
+    }
+
+    counts
+}
+
+impl BlueprintBody {
+    /// Header of the actor owning each power connection component, by component instance name.
+    /// Connections whose actor is not in the body are left out.
+    fn power_connections(&self) -> HashMap<&str, &ActorHeader> {
+        let actors: HashMap<&str, &ActorHeader> = self
+            .actor_headers()
+            .map(|actor| (actor.instance_name.as_str(), actor))
+            .collect();
+
+        self.object_headers
+            .iter()
+            .filter_map(|header| match header {
+                ObjectHeaderType::Component(header)
+                    if ConnectionKind::from_class(&header.class_name)
+                        == Some(ConnectionKind::Power) =>
+                {
+                    Some((
+                        header.instance_name.as_str(),
+                        *actors.get(header.parent_actor.as_str())?,
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every power line with its ends. The ends are found from the position of the power line
+    /// and the relative locations it caches, or from the positions of the connected buildings
+    /// when those are missing.
+    pub fn wires(&self) -> Vec<Wire> {
+        self.wires_with(&self.power_connections())
+    }
+
+    fn wires_with(&self, connections: &HashMap<&str, &ActorHeader>) -> Vec<Wire> {
+        self.entities()
+            .filter_map(|entity| {
+                let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                    (entity.header, entity.object)
+                else {
+                    return None;
+                };
+                let ExtraData::Wire { source, target } = &actor.extra_data else {
+                    return None;
+                };
+
+                let relative = |index| {
+                    actor.get::<Vec3>(&format!(
+                        "{WIRE_INSTANCES}[0].{CACHED_RELATIVE_LOCATIONS}[{index}]"
+                    ))
+                };
+                let position = |component: &str| {
+                    connections
+                        .get(component)
+                        .map(|actor| actor.position())
+                        .unwrap_or_default()
+                };
+                let (start, end) = match (relative(0), relative(1)) {
+                    (Some(start), Some(end)) => {
+                        let transform = header.transform();
+                        (
+                            transform.transform_point(start),
+                            transform.transform_point(end),
+                        )
+                    }
+                    _ => (position(&source.path_name), position(&target.path_name)),
+                };
+
+                Some(Wire {
+                    instance_name: header.instance_name.clone(),
+                    source: source.path_name.clone(),
+                    target: target.path_name.clone(),
+                    start,
+                    end,
+                })
+            })
+            .collect()
+    }
+
+    /// Number of power lines attached to the power connection `component`.
+    pub fn wire_count(&self, component: &str) -> usize {
+        self.objects
+            .iter()
+            .filter_map(|object| match object {
+                ObjectType::Actor(ActorObject {
+                    extra_data: ExtraData::Wire { source, target },
+                    ..
+                }) => Some([source, target]),
+                _ => None,
+            })
+            .flatten()
+            .filter(|end| end.path_name == component)
+            .count()
+    }
+
+    /// Adds a power line between the power connections `source` and `target`, which are
+    /// component instance names. The wire runs between the positions of their buildings and is
+    /// added to the `mWires` of both connections. Returns the instance name of the new power
+    /// line.
+    pub fn add_wire(&mut self, source: &str, target: &str) -> Result<String> {
+        if source == target {
+            bail!("Cannot wire {source:?} to itself");
+        }
+
+        let connections = self.power_connections();
+        let wires = self.wires_with(&connections);
+        let counts = wire_counts(&wires);
+        let mut ends = [Vec3::ZERO; 2];
+        for (end, component) in ends.iter_mut().zip([source, target]) {
+            let actor = connections.get(component).ok_or_else(|| {
+                eyre!("{component:?} is not a power connection of an actor in the body")
+            })?;
+            if let Some(max) = max_wires(&actor.type_path)
+                && counts.get(component).copied().unwrap_or_default() >= max
+            {
+                bail!("Power connection {component:?} already has {max} wires");
+            }
+            *end = actor.position();
+        }
+        let [start, end] = ends;
+
+        let instance_name = self.instance_names().allocate(POWER_LINE);
+        let header = ActorHeader::new(
+            POWER_LINE,
+            instance_name.clone(),
+            &Transform::from_translation(end),
+        );
+
+        let mut wire_instance = PropertyList(Vec::new());
+        wire_instance.insert(&format!("{LOCATIONS}[0]"), start)?;
+        wire_instance.insert(&format!("{LOCATIONS}[1]"), end)?;
+        wire_instance.insert(&format!("{CACHED_RELATIVE_LOCATIONS}[0]"), start - end)?;
+        wire_instance.insert(&format!("{CACHED_RELATIVE_LOCATIONS}[1]"), Vec3::ZERO)?;
+
+        let mut properties = PropertyList(Vec::new());
+        properties.insert(
+            WIRE_INSTANCES,
+            ArrayValue::Struct {
+                name: format!("{WIRE_INSTANCES}\0"),
+                struct_name: WIRE_INSTANCE.to_owned(),
+                elements: vec![StructDataType::Other {
+                    name: WIRE_INSTANCE.to_owned(),
+                    list: wire_instance,
+                }],
+            },
+        )?;
+        properties.insert(CACHED_LENGTH, start.distance(end) as f32)?;
+        let mut customization = CustomizationData::default();
+        customization.set_swatch_slot(0);
+        customization.write(&mut properties)?;
+        properties.insert(BUILT_WITH_RECIPE, POWER_LINE_RECIPE)?;
+
+        let actor = ActorObject {
+            extra_data: ExtraData::Wire {
+                source: ObjectRef::level(source),
+                target: ObjectRef::level(target),
+            },
+            ..ActorObject::buildable(properties)
+        };
+        self.insert(ObjectHeaderType::Actor(header), ObjectType::Actor(actor))?;
+
+        for component in [source, target] {
+            let entity = self
+                .entity_mut(component)
+                .ok_or_else(|| eyre!("Power connection {component:?} is not in the body"))?;
+            let properties = entity.object.properties_mut();
+            match properties.get::<&ArrayValue>(WIRES).map(ArrayValue::len) {
+                Some(len) => {
+                    properties.insert(&format!("{WIRES}[{len}]"), instance_name.as_str())?
+                }
+                None => properties.insert(
+                    WIRES,
+                    ArrayValue::Object(vec![ObjectRef::level(&instance_name)]),
+                )?,
+            }
+        }
+
+        Ok(instance_name)
+    }
+
+    /// Removes the power line `instance_name` and detaches it from the `mWires` of its power
+    /// connections.
+    pub fn remove_wire(&mut self, instance_name: &str) -> Result<Wire> {
+        let wire = self
+            .wires()
+            .into_iter()
+            .find(|wire| wire.instance_name == instance_name)
+            .ok_or_else(|| eyre!("{instance_name:?} is not a power line in the body"))?;
+
+        self.remove(instance_name);
+        for component in [&wire.source, &wire.target] {
+            let Some(entity) = self.entity_mut(component) else {
+                continue;
+            };
+            let properties = entity.object.properties_mut();
+            let Some(ArrayValue::Object(wires)) = properties.get::<&ArrayValue>(WIRES) else {
+                continue;
+            };
+            let attached: Vec<usize> = wires
+                .iter()
+                .enumerate()
+                .filter(|(_, wire)| wire.path_name == instance_name)
+                .map(|(index, _)| index)
+                .collect();
+            for index in attached.into_iter().rev() {
+                properties.remove(&format!("{WIRES}[{index}]"));
+            }
+        }
+
+        Ok(wire)
+    }
+
+    /// Checks every power line ends at a power connection in the body and that no power pole has
+    /// more wires than it takes.
+    pub fn validate_power(&self) -> Vec<PowerError> {
+        let connections = self.power_connections();
+        let wires = self.wires_with(&connections);
+        let counts = wire_counts(&wires);
+        let mut errors = Vec::new();
+
+        for wire in &wires {
+            for component in [&wire.source, &wire.target] {
+                if !connections.contains_key(component.as_str()) {
+                    errors.push(PowerError::MissingConnection {
+                        wire: wire.instance_name.clone(),
+                        component: component.clone(),
+                    });
+                }
+            }
+        }
+
+        // Headers are walked in order so errors come out in body order
+        for header in &self.object_headers {
+            let ObjectHeaderType::Component(component) = header else {
+                continue;
+            };
+            let name = component.instance_name.as_str();
+            let Some(max) = connections
+                .get(name)
+                .and_then(|actor| max_wires(&actor.type_path))
+            else {
+                continue;
+            };
+
+            let count = counts.get(name).copied().unwrap_or_default();
+            if count > max {
+                errors.push(PowerError::TooManyWires {
+                    component: component.instance_name.clone(),
+                    wires: count,
+                    max,
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{
+            Blueprint,
+            body::{MirrorAxis, MirrorTable, blueprint_body},
+        },
+        transform::Quat,
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    /// Power connections of the buildings of type `pattern`.
+    fn power_connections(body: &BlueprintBody, pattern: &str) -> Vec<String> {
+        body.query()
+            .type_path(pattern)
+            .iter()
+            .flat_map(|actor| {
+                body.object_headers
+                    .iter()
+                    .filter_map(move |header| match header {
+                        ObjectHeaderType::Component(component)
+                            if component.parent_actor == actor.instance_name()
+                                && ConnectionKind::from_class(&component.class_name)
+                                    == Some(ConnectionKind::Power) =>
+                        {
+                            Some(component.instance_name.clone())
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_wires() {
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let body = &blueprint.body;
+        let wires = body.wires();
+        assert_eq!(wires.len(), 3);
+
+        for wire in &wires {
+            let ObjectType::Actor(actor) = body
+                .entity(&wire.instance_name)
+                .expect("Wire should exist")
+                .object
+            else {
+                panic!("Expected an actor object");
+            };
+            let cached = actor.get::<f32>(CACHED_LENGTH).expect("Length is cached");
+            assert!((wire.length() - f64::from(cached)).abs() < 1.0);
+        }
+
+        let pole = &power_connections(body, "*Build_PowerPoleMk1_C")[0];
+        assert_eq!(body.wire_count(pole), 3);
+        assert_eq!(body.validate_power(), []);
+    }
+
+    #[test]
+    fn check_wires_follow_transform() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let before = body.wires();
+
+        let transform = Transform::new(
+            Quat::from_yaw(90.0),
+            Vec3::new(800.0, 400.0, 0.0),
+            Vec3::ONE,
+        );
+        body.transform(&transform);
+        for (before, after) in before.iter().zip(body.wires()) {
+            assert!(
+                after
+                    .start
+                    .abs_diff_eq(transform.transform_point(before.start), 0.1)
+            );
+            assert!(
+                after
+                    .end
+                    .abs_diff_eq(transform.transform_point(before.end), 0.1)
+            );
+        }
+    }
+
+    #[test]
+    fn check_wires_follow_mirror() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let before = body.wires();
+
+        body.mirror(MirrorAxis::X, &MirrorTable::new());
+        let after = body.wires();
+        assert_eq!(after.len(), before.len());
+        for (before, after) in before.iter().zip(&after) {
+            assert_eq!(after.source, before.source);
+            assert_eq!(after.target, before.target);
+            assert!(after.start.abs_diff_eq(before.start.mirror(Vec3::X), 0.1));
+            assert!(after.end.abs_diff_eq(before.end.mirror(Vec3::X), 0.1));
+            assert!((after.length() - before.length()).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn check_add_remove_wire() {
+        let original = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let mut body = original.clone();
+        let pole = power_connections(&body, "*Build_PowerPoleMk1_C").remove(0);
+        let generators = power_connections(&body, "*Build_GeneratorCoal_C");
+
+        let name = body
+            .add_wire(&pole, &generators[0])
+            .expect("Pole should take a fourth wire");
+        assert_eq!(body.wire_count(&pole), 4);
+        assert_eq!(
+            body.entity(&pole)
+                .and_then(|entity| entity.object.properties().get::<&ArrayValue>(WIRES))
+                .map(ArrayValue::len),
+            Some(4)
+        );
+        let wire = body
+            .wires()
+            .into_iter()
+            .find(|wire| wire.instance_name == name)
+            .expect("Wire should be listed");
+        assert_eq!(wire.source, pole);
+        assert_eq!(wire.target, generators[0]);
+        assert!(
+            (wire.length()
+                - Vec3::new(-1000.0, -200.0, 0.0).distance(Vec3::new(-50.0, 1550.0, 0.0)))
+            .abs()
+                < 0.1
+        );
+        assert_eq!(body.validate_names(), []);
+        assert_eq!(body.validate_power(), []);
+
+        // The new power line is written and read back like the others
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+
+        assert!(body.add_wire(&pole, &generators[1]).is_err());
+        assert!(body.add_wire(&pole, &pole).is_err());
+        assert!(body.add_wire(&pole, "Missing\0").is_err());
+
+        let removed = body.remove_wire(&name).expect("Wire should be removed");
+        assert_eq!(removed, wire);
+        assert_eq!(body, original);
+        assert!(body.remove_wire(&name).is_err());
+    }
+
+    #[test]
+    fn check_missing_connection() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let generator = body
+            .query()
+            .type_path("*Build_GeneratorCoal_C")
+            .iter()
+            .next()
+            .map(|entity| entity.instance_name().to_owned())
+            .expect("Blueprint should have a generator");
+        body.remove(&generator);
+
+        let errors = body.validate_power();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], PowerError::MissingConnection { .. }));
+    }
+}
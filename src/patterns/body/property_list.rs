@@ -1,6 +1,11 @@
+mod array_property;
+mod bool_property;
 mod byte_property;
+mod enum_property;
 mod float_property;
+mod int_property;
 mod object_property;
+mod str_property;
 mod struct_property;
 
 use std::io::Write;
@@ -11,9 +16,14 @@ use winnow::{
     error::StrContext,
 };
 
+pub use array_property::*;
+pub use bool_property::*;
 pub use byte_property::*;
+pub use enum_property::*;
 pub use float_property::*;
+pub use int_property::*;
 pub use object_property::*;
+pub use str_property::*;
 pub use struct_property::*;
 
 use crate::{
@@ -23,9 +33,14 @@ use crate::{
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PropertyType {
+    ArrayProperty(ArrayProperty),
+    BoolProperty(BoolProperty),
     ByteProperty(ByteProperty),
+    EnumProperty(EnumProperty),
     FloatProperty(FloatProperty),
+    IntProperty(IntProperty),
     ObjectProperty(ObjectProperty),
+    StrProperty(StrProperty),
     StructProperty(StructProperty),
     None,
 }
@@ -42,6 +57,24 @@ pub enum PropertyType {
 //     }
 // }
 
+impl PropertyType {
+    /// Index of the property among the properties of the same name, which store fixed size arrays
+    pub fn index(&self) -> u32 {
+        match self {
+            PropertyType::ArrayProperty(array_property) => array_property.index,
+            PropertyType::BoolProperty(bool_property) => bool_property.index,
+            PropertyType::ByteProperty(byte_property) => byte_property.index(),
+            PropertyType::EnumProperty(enum_property) => enum_property.index,
+            PropertyType::FloatProperty(float_property) => float_property.index,
+            PropertyType::IntProperty(int_property) => int_property.index,
+            PropertyType::ObjectProperty(object_property) => object_property.index,
+            PropertyType::StrProperty(str_property) => str_property.index,
+            PropertyType::StructProperty(struct_property) => struct_property.index,
+            PropertyType::None => 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Property {
     pub name: String,
@@ -54,10 +87,15 @@ impl Property {
     // const OP: &'static FStr = FStr::new("ObjectProperty\0");
     // const SP: &'static FStr = FStr::new("StructProperty\0");
 
-    const BP: &'static str = "ByteProperty\0";
-    const FP: &'static str = "FloatProperty\0";
-    const OP: &'static str = "ObjectProperty\0";
-    const SP: &'static str = "StructProperty\0";
+    pub const AP: &'static str = "ArrayProperty\0";
+    pub const BOP: &'static str = "BoolProperty\0";
+    pub const BP: &'static str = "ByteProperty\0";
+    pub const EP: &'static str = "EnumProperty\0";
+    pub const FP: &'static str = "FloatProperty\0";
+    pub const IP: &'static str = "IntProperty\0";
+    pub const OP: &'static str = "ObjectProperty\0";
+    pub const STP: &'static str = "StrProperty\0";
+    pub const SP: &'static str = "StructProperty\0";
 
     fn get_none_property() -> Property {
         const NAME: &str = "None\0";
@@ -71,11 +109,16 @@ impl Property {
     pub fn size(&self) -> u32 {
         let name_size = self.name.size();
         let type_size = match &self.property {
+            PropertyType::ArrayProperty(array_property) => array_property.size() + Self::AP.size(),
+            PropertyType::BoolProperty(bool_property) => bool_property.size() + Self::BOP.size(),
+            PropertyType::EnumProperty(enum_property) => enum_property.size() + Self::EP.size(),
+            PropertyType::IntProperty(int_property) => int_property.size() + Self::IP.size(),
             PropertyType::ByteProperty(byte_property) => byte_property.size() + Self::BP.size(),
             PropertyType::FloatProperty(_) => 13 + Self::FP.size(),
             PropertyType::ObjectProperty(object_property) => {
                 object_property.size() + Self::OP.size()
             }
+            PropertyType::StrProperty(str_property) => str_property.size() + Self::STP.size(),
             PropertyType::StructProperty(struct_property) => {
                 struct_property.size() + Self::SP.size()
             }
@@ -90,6 +133,22 @@ impl<W: Write> BPWrite<W> for &Property {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
         self.name.bp_write(writer)?;
         match &self.property {
+            PropertyType::ArrayProperty(array_property) => {
+                Property::AP.bp_write(writer)?;
+                array_property.bp_write(writer)?
+            }
+            PropertyType::BoolProperty(bool_property) => {
+                Property::BOP.bp_write(writer)?;
+                bool_property.bp_write(writer)?
+            }
+            PropertyType::EnumProperty(enum_property) => {
+                Property::EP.bp_write(writer)?;
+                enum_property.bp_write(writer)?
+            }
+            PropertyType::IntProperty(int_property) => {
+                Property::IP.bp_write(writer)?;
+                int_property.bp_write(writer)?
+            }
             PropertyType::ByteProperty(byte_property) => {
                 Property::BP.bp_write(writer)?;
                 byte_property.bp_write(writer)?
@@ -102,6 +161,10 @@ impl<W: Write> BPWrite<W> for &Property {
                 Property::OP.bp_write(writer)?;
                 object_property.bp_write(writer)?
             }
+            PropertyType::StrProperty(str_property) => {
+                Property::STP.bp_write(writer)?;
+                str_property.bp_write(writer)?
+            }
             PropertyType::StructProperty(struct_property) => {
                 Property::SP.bp_write(writer)?;
                 struct_property.bp_write(writer)?
@@ -113,7 +176,7 @@ impl<W: Write> BPWrite<W> for &Property {
     }
 }
 
-fn none_property<'d>(data: &mut &'d Bytes) -> winnow::Result<Property> {
+fn none_property(data: &mut &Bytes) -> winnow::Result<Property> {
     const NP: &str = "None\0";
     seq! { Property {
         name: fstring.verify(|s: &str| s == NP).context(StrContext::Label("name")).map(ToOwned::to_owned),
@@ -122,13 +185,18 @@ fn none_property<'d>(data: &mut &'d Bytes) -> winnow::Result<Property> {
     .parse_next(data)
 }
 
-fn some_property<'d>(data: &mut &'d Bytes) -> winnow::Result<Property> {
+fn some_property(data: &mut &Bytes) -> winnow::Result<Property> {
     seq! {Property {
-        name: fstring.context(StrContext::Label("property name")).map(ToOwned::to_owned),
-        property: dispatch! {fstring.map(|t| t.as_ref()).context(StrContext::Label("property type"));
+        name: fstring.verify(|s: &str| s != "None\0").context(StrContext::Label("property name")).map(ToOwned::to_owned),
+        property: dispatch! {fstring.context(StrContext::Label("property type"));
+            Property::AP => array_property.map(PropertyType::ArrayProperty),
+            Property::BOP => bool_property.map(PropertyType::BoolProperty),
             Property::BP => byte_property.map(PropertyType::ByteProperty),
+            Property::EP => enum_property.map(PropertyType::EnumProperty),
             Property::FP => float_property.map(PropertyType::FloatProperty),
+            Property::IP => int_property.map(PropertyType::IntProperty),
             Property::OP => object_property.map(PropertyType::ObjectProperty),
+            Property::STP => str_property.map(PropertyType::StrProperty),
             Property::SP => struct_property.map(PropertyType::StructProperty),
             _ => fail.context(StrContext::Label("unkown property")),
         }
@@ -136,7 +204,7 @@ fn some_property<'d>(data: &mut &'d Bytes) -> winnow::Result<Property> {
     .parse_next(data)
 }
 
-fn property<'d>(data: &mut &'d Bytes) -> winnow::Result<Property> {
+pub fn property(data: &mut &Bytes) -> winnow::Result<Property> {
     alt((
         none_property.context(StrContext::Label("none property")),
         some_property.context(StrContext::Label("data containing property")),
@@ -151,6 +219,104 @@ impl PropertyList {
     pub fn size(&self) -> u32 {
         self.0.iter().map(|p| p.size()).sum::<u32>() + Property::get_none_property().size()
     }
+
+    /// Finds a property by its name, given without the trailing nul.
+    pub fn find(&self, name: &str) -> Option<&Property> {
+        self.0
+            .iter()
+            .find(|prop| prop.name.trim_end_matches('\0') == name)
+    }
+
+    /// Removes the object properties referencing objects for which `dangling` returns true and
+    /// clears such entries of object arrays, including in nested structs.
+    /// Returns the references that were pruned.
+    pub fn prune_references(&mut self, dangling: &impl Fn(&str) -> bool) -> Vec<String> {
+        let mut pruned = Vec::new();
+
+        self.0.retain_mut(|prop| match &mut prop.property {
+            PropertyType::ObjectProperty(object_property) => {
+                let keep = !dangling(&object_property.reference);
+                if !keep {
+                    pruned.push(object_property.reference.clone());
+                }
+
+                keep
+            }
+            PropertyType::StructProperty(struct_property) => {
+                pruned.append(&mut struct_property.data.prune_references(dangling));
+                true
+            }
+            PropertyType::ArrayProperty(array_property) => {
+                match &mut array_property.value {
+                    ArrayValue::Object(objects) => {
+                        for object in objects.iter_mut().filter(|o| dangling(&o.path_name)) {
+                            object.level_name.clear();
+                            pruned.push(std::mem::take(&mut object.path_name));
+                        }
+                    }
+                    ArrayValue::Struct { elements, .. } => {
+                        for element in elements {
+                            pruned.append(&mut element.prune_references(dangling));
+                        }
+                    }
+                    _ => {}
+                }
+
+                true
+            }
+            _ => true,
+        });
+
+        pruned
+    }
+
+    /// Calls `f` with the path name of every object referenced in the list, including the ones
+    /// nested in structs and arrays.
+    pub fn for_each_path_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        for prop in &mut self.0 {
+            match &mut prop.property {
+                PropertyType::ObjectProperty(object_property) => f(&mut object_property.reference),
+                PropertyType::StructProperty(struct_property) => {
+                    struct_property.data.for_each_path_name_mut(f)
+                }
+                PropertyType::ArrayProperty(array_property) => match &mut array_property.value {
+                    ArrayValue::Object(objects) => {
+                        for object in objects {
+                            f(&mut object.path_name);
+                        }
+                    }
+                    ArrayValue::Struct { elements, .. } => {
+                        for element in elements {
+                            element.for_each_path_name_mut(f);
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Calls `f` with the property name and data of every struct in the list that is not itself a
+    /// property list, such as `Vector` and `Quat`, including the ones nested in structs and arrays.
+    pub fn for_each_struct_mut(&mut self, f: &mut impl FnMut(&str, &mut StructDataType)) {
+        for prop in &mut self.0 {
+            match &mut prop.property {
+                PropertyType::StructProperty(struct_property) => {
+                    struct_property.data.for_each_struct_mut(&prop.name, f)
+                }
+                PropertyType::ArrayProperty(ArrayProperty {
+                    value: ArrayValue::Struct { elements, .. },
+                    ..
+                }) => {
+                    for element in elements {
+                        element.for_each_struct_mut(&prop.name, f);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
 }
 
 impl<W: Write> BPWrite<W> for &PropertyList {
@@ -163,16 +329,16 @@ impl<W: Write> BPWrite<W> for &PropertyList {
     }
 }
 
-impl<'d> AsRef<[Property]> for PropertyList {
+impl AsRef<[Property]> for PropertyList {
     fn as_ref(&self) -> &[Property] {
         self.0.as_ref()
     }
 }
 
-pub fn property_list<'d>(data: &mut &'d Bytes) -> winnow::Result<PropertyList> {
+pub fn property_list(data: &mut &Bytes) -> winnow::Result<PropertyList> {
     terminated(
         repeat(
-            1..,
+            0..,
             some_property.context(StrContext::Label("data containing property")),
         ),
         none_property.context(StrContext::Label("terminating none property")),
@@ -0,0 +1,247 @@
+use std::io::Write;
+
+use winnow::{
+    Bytes, Parser,
+    binary::{le_i32, le_u8, le_u32},
+    combinator::{fail, repeat, terminated},
+    error::StrContext,
+};
+
+use crate::{
+    bp_write::BPWrite,
+    patterns::{
+        body::{
+            ObjectRef, object_ref,
+            property_list::{Property, StructDataType, struct_data},
+        },
+        factory_string::{FStringExt, fstring},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayValue {
+    Byte(Vec<u8>),
+    Int(Vec<i32>),
+    Object(Vec<ObjectRef>),
+    Struct {
+        // Struct arrays repeat the property name and struct type in an inner tag
+        name: String,
+        struct_name: String,
+        elements: Vec<StructDataType>,
+    },
+}
+
+impl ArrayValue {
+    pub fn inner_type(&self) -> &'static str {
+        match self {
+            ArrayValue::Byte(_) => Property::BP,
+            ArrayValue::Int(_) => Property::IP,
+            ArrayValue::Object(_) => Property::OP,
+            ArrayValue::Struct { .. } => Property::SP,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ArrayValue::Byte(values) => values.len(),
+            ArrayValue::Int(values) => values.len(),
+            ArrayValue::Object(values) => values.len(),
+            ArrayValue::Struct { elements, .. } => elements.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn elements_size(&self) -> u32 {
+        match self {
+            ArrayValue::Byte(values) => values.len() as u32,
+            ArrayValue::Int(values) => values.len() as u32 * 4,
+            ArrayValue::Object(values) => values.iter().map(ObjectRef::size).sum(),
+            ArrayValue::Struct { elements, .. } => elements.iter().map(StructDataType::size).sum(),
+        }
+    }
+
+    /// Size of the array data, which includes the count and the inner tag of struct arrays
+    pub fn size(&self) -> u32 {
+        let tag_size = match self {
+            ArrayValue::Struct {
+                name, struct_name, ..
+            } => name.size() + Property::SP.size() + struct_name.size() + 25,
+            _ => 0,
+        };
+
+        self.elements_size() + tag_size + 4
+    }
+}
+
+impl<W: Write> BPWrite<W> for &ArrayValue {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        let count: u32 = self.len().try_into().expect("Array is too long");
+        count.bp_write(writer)?;
+
+        match self {
+            ArrayValue::Byte(values) => values.as_slice().bp_write(writer),
+            ArrayValue::Int(values) => {
+                for value in values {
+                    value.bp_write(writer)?;
+                }
+
+                Ok(())
+            }
+            ArrayValue::Object(values) => values.bp_write(writer),
+            ArrayValue::Struct {
+                name,
+                struct_name,
+                elements,
+            } => {
+                name.bp_write(writer)?;
+                Property::SP.bp_write(writer)?;
+                self.elements_size().bp_write(writer)?;
+                0u32.bp_write(writer)?;
+                struct_name.bp_write(writer)?;
+                [0u8; 17].bp_write(writer)?;
+                elements.bp_write(writer)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayProperty {
+    pub index: u32,
+    pub value: ArrayValue,
+}
+
+impl ArrayProperty {
+    pub fn size(&self) -> u32 {
+        self.value.inner_type().size() + self.value.size() + 9
+    }
+}
+
+impl<W: Write> BPWrite<W> for &ArrayProperty {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.value.size().bp_write(writer)?;
+        self.index.bp_write(writer)?;
+        self.value.inner_type().bp_write(writer)?;
+        0u8.bp_write(writer)?;
+        self.value.bp_write(writer)
+    }
+}
+
+fn struct_array(data: &mut &Bytes) -> winnow::Result<ArrayValue> {
+    let count = le_u32
+        .context(StrContext::Label("array count"))
+        .parse_next(data)?;
+    let name = fstring
+        .context(StrContext::Label("inner property name"))
+        .parse_next(data)?;
+    let (_, _, _, struct_name) = (
+        fstring
+            .verify(|s: &str| s == Property::SP)
+            .context(StrContext::Label("inner property type")),
+        le_u32.context(StrContext::Label("inner size")),
+        le_u32.context(StrContext::Label("inner index")),
+        terminated(fstring, &[0; 17]).context(StrContext::Label("inner struct name")),
+    )
+        .parse_next(data)?;
+    let elements = repeat(count as usize, |data: &mut &Bytes| {
+        struct_data(struct_name, data)
+    })
+    .context(StrContext::Label("struct elements"))
+    .parse_next(data)?;
+
+    Ok(ArrayValue::Struct {
+        name: name.to_owned(),
+        struct_name: struct_name.to_owned(),
+        elements,
+    })
+}
+
+pub fn array_property(data: &mut &Bytes) -> winnow::Result<ArrayProperty> {
+    let (_, index) = (le_u32, le_u32.context(StrContext::Label("array index"))).parse_next(data)?;
+    let inner_type = terminated(fstring, &[0])
+        .context(StrContext::Label("array inner type"))
+        .parse_next(data)?;
+
+    let value = match inner_type {
+        Property::BP => le_u32
+            .flat_map(|count| repeat(count as usize, le_u8))
+            .map(ArrayValue::Byte)
+            .parse_next(data)?,
+        Property::IP => le_u32
+            .flat_map(|count| repeat(count as usize, le_i32))
+            .map(ArrayValue::Int)
+            .parse_next(data)?,
+        Property::OP => le_u32
+            .flat_map(|count| repeat(count as usize, object_ref))
+            .map(ArrayValue::Object)
+            .parse_next(data)?,
+        Property::SP => struct_array.parse_next(data)?,
+        _ => fail
+            .context(StrContext::Label("unknown array type"))
+            .parse_next(data)?,
+    };
+
+    Ok(ArrayProperty { index, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_int_array() {
+        const DATA: [u8; 0x25] = [
+            0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x49, 0x6E,
+            0x74, 0x50, 0x72, 0x6F, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x02, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x50, 0xC3, 0x00, 0x00,
+        ];
+
+        let prop = array_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(prop.value, ArrayValue::Int(vec![0, 50000]));
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+
+    #[test]
+    fn check_object_array() {
+        const DATA: [u8; 0x76] = [
+            0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x4F, 0x62,
+            0x6A, 0x65, 0x63, 0x74, 0x50, 0x72, 0x6F, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x46, 0x00, 0x00, 0x00, 0x2F, 0x47, 0x61, 0x6D, 0x65, 0x2F, 0x46, 0x61,
+            0x63, 0x74, 0x6F, 0x72, 0x79, 0x47, 0x61, 0x6D, 0x65, 0x2F, 0x52, 0x65, 0x73, 0x6F,
+            0x75, 0x72, 0x63, 0x65, 0x2F, 0x52, 0x61, 0x77, 0x52, 0x65, 0x73, 0x6F, 0x75, 0x72,
+            0x63, 0x65, 0x73, 0x2F, 0x57, 0x61, 0x74, 0x65, 0x72, 0x2F, 0x44, 0x65, 0x73, 0x63,
+            0x5F, 0x57, 0x61, 0x74, 0x65, 0x72, 0x2E, 0x44, 0x65, 0x73, 0x63, 0x5F, 0x57, 0x61,
+            0x74, 0x65, 0x72, 0x5F, 0x43, 0x00,
+        ];
+
+        let prop = array_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+        let ArrayValue::Object(objects) = &prop.value else {
+            panic!("Expected an object array");
+        };
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].path_name, "");
+        assert_eq!(
+            objects[1].path_name,
+            "/Game/FactoryGame/Resource/RawResources/Water/Desc_Water.Desc_Water_C\0"
+        );
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use winnow::{Bytes, Parser, binary::le_u32, combinator::seq, error::StrContext, token::any};
+
+use crate::bp_write::BPWrite;
+
+// Bools store their value in the tag and have a data size of zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoolProperty {
+    pub index: u32,
+    pub value: bool,
+}
+
+impl BoolProperty {
+    pub fn size(&self) -> u32 {
+        10
+    }
+}
+
+impl<W: Write> BPWrite<W> for &BoolProperty {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        0u32.bp_write(writer)?;
+        self.index.bp_write(writer)?;
+        u8::from(self.value).bp_write(writer)?;
+        0u8.bp_write(writer)
+    }
+}
+
+pub fn bool_property(data: &mut &Bytes) -> winnow::Result<BoolProperty> {
+    seq! {BoolProperty {
+        _: &[0; 4],
+        index: le_u32.context(StrContext::Label("bool index")),
+        value: any.map(|b: u8| b != 0).context(StrContext::Label("bool value")),
+        _: &[0],
+    }}
+    .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bool_property() {
+        const DATA: [u8; 0x0A] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+
+        let prop = bool_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(prop.index, 0);
+        assert!(prop.value);
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+}
@@ -27,7 +27,7 @@ impl<W: Write> BPWrite<W> for &ByteType {
     }
 }
 
-fn byte_type<'d>(data: &mut &Bytes) -> winnow::Result<ByteType> {
+fn byte_type(data: &mut &Bytes) -> winnow::Result<ByteType> {
     preceded(
         fstring.verify(|s: &str| s == "None\0"),
         preceded(&[0u8], le_u8.map(ByteType::Byte)),
@@ -35,7 +35,7 @@ fn byte_type<'d>(data: &mut &Bytes) -> winnow::Result<ByteType> {
     .parse_next(data)
 }
 
-fn fstring_type<'d>(data: &mut &'d Bytes) -> winnow::Result<ByteType> {
+fn fstring_type(_data: &mut &Bytes) -> winnow::Result<ByteType> {
     unimplemented!()
 }
 
@@ -46,6 +46,22 @@ pub struct ByteProperty {
 }
 
 impl ByteProperty {
+    pub fn new(index: u32, value: ByteType) -> Self {
+        ByteProperty { index, value }
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn value(&self) -> &ByteType {
+        &self.value
+    }
+
+    pub fn set_value(&mut self, value: ByteType) {
+        self.value = value;
+    }
+
     pub fn size(&self) -> u32 {
         match &self.value {
             ByteType::Byte(_) => 19,
@@ -76,7 +92,7 @@ impl<W: Write> BPWrite<W> for &ByteProperty {
     }
 }
 
-pub fn byte_property<'d>(data: &mut &'d Bytes) -> winnow::Result<ByteProperty> {
+pub fn byte_property(data: &mut &Bytes) -> winnow::Result<ByteProperty> {
     seq! { ByteProperty {
         _: le_u32.context(StrContext::Label("size")),
         index: le_u32.context(StrContext::Label("index")),
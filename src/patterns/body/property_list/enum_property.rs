@@ -0,0 +1,71 @@
+use std::io::Write;
+
+use winnow::{Bytes, Parser, binary::le_u32, combinator::seq, error::StrContext};
+
+use crate::{
+    bp_write::BPWrite,
+    patterns::factory_string::{FStringExt, fstring},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumProperty {
+    pub index: u32,
+    pub enum_name: String,
+    pub value: String,
+}
+
+impl EnumProperty {
+    pub fn size(&self) -> u32 {
+        self.enum_name.size() + self.value.size() + 9
+    }
+}
+
+impl<W: Write> BPWrite<W> for &EnumProperty {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.value.size().bp_write(writer)?;
+        self.index.bp_write(writer)?;
+        self.enum_name.bp_write(writer)?;
+        0u8.bp_write(writer)?;
+        self.value.bp_write(writer)
+    }
+}
+
+pub fn enum_property(data: &mut &Bytes) -> winnow::Result<EnumProperty> {
+    seq! {EnumProperty {
+        _: le_u32,
+        index: le_u32.context(StrContext::Label("enum index")),
+        enum_name: fstring.context(StrContext::Label("enum name")).map(ToOwned::to_owned),
+        _: &[0],
+        value: fstring.context(StrContext::Label("enum value")).map(ToOwned::to_owned),
+    }}
+    .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_enum_property() {
+        const DATA: [u8; 0x54] = [
+            0x2B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x45, 0x46,
+            0x61, 0x63, 0x74, 0x6F, 0x72, 0x79, 0x43, 0x6F, 0x6E, 0x6E, 0x65, 0x63, 0x74, 0x69,
+            0x6F, 0x6E, 0x44, 0x69, 0x72, 0x65, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x00, 0x00, 0x27,
+            0x00, 0x00, 0x00, 0x45, 0x46, 0x61, 0x63, 0x74, 0x6F, 0x72, 0x79, 0x43, 0x6F, 0x6E,
+            0x6E, 0x65, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x44, 0x69, 0x72, 0x65, 0x63, 0x74, 0x69,
+            0x6F, 0x6E, 0x3A, 0x3A, 0x46, 0x43, 0x44, 0x5F, 0x49, 0x4E, 0x50, 0x55, 0x54, 0x00,
+        ];
+
+        let prop = enum_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(prop.enum_name, "EFactoryConnectionDirection\0");
+        assert_eq!(prop.value, "EFactoryConnectionDirection::FCD_INPUT\0");
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+}
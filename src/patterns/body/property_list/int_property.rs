@@ -0,0 +1,65 @@
+use std::io::Write;
+
+use winnow::{
+    Bytes, Parser,
+    binary::{le_i32, le_u32},
+    combinator::seq,
+    error::StrContext,
+};
+
+use crate::bp_write::BPWrite;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntProperty {
+    pub index: u32,
+    pub value: i32,
+}
+
+impl IntProperty {
+    pub fn size(&self) -> u32 {
+        13
+    }
+}
+
+impl<W: Write> BPWrite<W> for &IntProperty {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        4u32.bp_write(writer)?;
+        self.index.bp_write(writer)?;
+        0u8.bp_write(writer)?;
+        self.value.bp_write(writer)
+    }
+}
+
+pub fn int_property(data: &mut &Bytes) -> winnow::Result<IntProperty> {
+    seq! {IntProperty {
+        _: le_u32,
+        index: le_u32.context(StrContext::Label("int index")),
+        _: &[0],
+        value: le_i32.context(StrContext::Label("int value")),
+    }}
+    .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_int_property() {
+        const DATA: [u8; 0x0D] = [
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+
+        let prop = int_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(prop.index, 0);
+        assert_eq!(prop.value, -1);
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+}
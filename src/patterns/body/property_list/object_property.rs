@@ -10,30 +10,34 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ObjectProperty {
     pub index: u32,
+    // Empty for asset references such as recipes, set for references to other objects
+    pub level_name: String,
     pub reference: String,
 }
 
 impl ObjectProperty {
     pub fn size(&self) -> u32 {
-        self.reference.size() + 13
+        self.level_name.size() + self.reference.size() + 9
     }
 }
 
 impl<W: Write> BPWrite<W> for &ObjectProperty {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
-        let size = self.reference.size() + 4;
+        let size = self.level_name.size() + self.reference.size();
         size.bp_write(writer)?;
         self.index.bp_write(writer)?;
-        [0u8; 5].bp_write(writer)?;
+        0u8.bp_write(writer)?;
+        self.level_name.bp_write(writer)?;
         self.reference.bp_write(writer)
     }
 }
 
-pub fn object_property<'d>(data: &mut &'d Bytes) -> winnow::Result<ObjectProperty> {
+pub fn object_property(data: &mut &Bytes) -> winnow::Result<ObjectProperty> {
     seq! { ObjectProperty {
         _: le_u32,
         index: le_u32.context(StrContext::Label("object index")),
-        _: &[0; 5],
+        _: &[0],
+        level_name: fstring.context(StrContext::Label("object level name")).map(ToOwned::to_owned),
         reference: fstring.context(StrContext::Label("object reference")).map(ToOwned::to_owned),
     }}
     .parse_next(data)
@@ -61,6 +65,7 @@ mod tests {
             .expect("Parse should succeed");
 
         assert_eq!(prop.index, 0);
+        assert_eq!(prop.level_name, "");
         assert_eq!(
             prop.reference,
             "/Game/FactoryGame/Prototype/Buildable/Beams/Recipe_Beam_Painted.Recipe_Beam_Painted_C\0",
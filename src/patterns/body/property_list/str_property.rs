@@ -0,0 +1,85 @@
+use std::{borrow::Cow, io::Write};
+
+use winnow::{Bytes, Parser, binary::le_u32, combinator::seq, error::StrContext};
+
+use crate::{
+    bp_write::BPWrite,
+    patterns::factory_string::{FStringExt, text_fstring, write_text_fstring},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrProperty {
+    pub index: u32,
+    // Includes the trailing nul unless the string is empty, stored as UTF-16 unless it is ASCII
+    pub value: String,
+}
+
+impl StrProperty {
+    pub fn size(&self) -> u32 {
+        self.value.text_size() + 9
+    }
+}
+
+impl<W: Write> BPWrite<W> for &StrProperty {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.value.text_size().bp_write(writer)?;
+        self.index.bp_write(writer)?;
+        0u8.bp_write(writer)?;
+        write_text_fstring(&self.value, writer)
+    }
+}
+
+pub fn str_property(data: &mut &Bytes) -> winnow::Result<StrProperty> {
+    seq! { StrProperty {
+        _: le_u32,
+        index: le_u32.context(StrContext::Label("string index")),
+        _: &[0],
+        value: text_fstring.context(StrContext::Label("string value")).map(Cow::into_owned),
+    }}
+    .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_str_property() {
+        const DATA: [u8; 0x18] = [
+            0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x43,
+            0x6F, 0x61, 0x6C, 0x20, 0x50, 0x6F, 0x77, 0x65, 0x72, 0x00,
+        ];
+
+        let prop = str_property
+            .parse(DATA.as_slice().into())
+            .expect("Parse should succeed");
+
+        assert_eq!(prop.index, 0);
+        assert_eq!(prop.value, "Coal Power\0");
+        assert_eq!(prop.size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+
+        assert_eq!(buf, DATA);
+    }
+
+    #[test]
+    fn check_wide_str_property() {
+        let prop = StrProperty {
+            index: 0,
+            value: "Kühlung 20°C\0".to_owned(),
+        };
+
+        let mut buf = Vec::new();
+        prop.bp_write(&mut buf).expect("Write should succeed");
+        assert_eq!(buf.len(), prop.size() as usize);
+        // 13 UTF-16 code units with the nul
+        assert_eq!(buf[9..13], (-13i32).to_le_bytes());
+
+        let parsed = str_property
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, prop);
+    }
+}
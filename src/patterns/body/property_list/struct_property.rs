@@ -2,80 +2,139 @@ use std::io::Write;
 
 use winnow::{
     Bytes, Parser,
-    binary::{le_f32, le_u32},
-    combinator::{dispatch, seq, terminated},
+    binary::{le_f32, le_f64, le_u32},
+    combinator::{seq, terminated},
     error::StrContext,
 };
 
 use crate::{
     bp_write::BPWrite,
     patterns::{
-        body::property_list::{PropertyList, property_list},
+        body::{
+            ObjectRef, object_ref,
+            property_list::{PropertyList, property_list},
+        },
         factory_string::{FStringExt, fstring},
     },
+    transform::{Quat, Vec3},
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum StructDataType {
     LinearColor(LinearColor),
+    Vector(Vec3),
+    Quat(Quat),
+    InventoryItem(InventoryItem),
+    FluidBox(FluidBox),
     Other { name: String, list: PropertyList },
 }
 
 impl StructDataType {
-    pub fn get_writable<'s, W: Write>(
-        &'s self,
-    ) -> (
-        u32,
-        &'s str,
-        Box<dyn FnOnce(&mut W) -> Result<(), std::io::Error> + 's>,
-    ) {
+    pub fn name(&self) -> &str {
         match self {
-            StructDataType::LinearColor(lc) => {
-                let size = 16;
-                let name = StructProperty::LC;
-                let write = |writer: &mut W| lc.bp_write(writer);
+            StructDataType::LinearColor(_) => StructProperty::LC,
+            StructDataType::Vector(_) => StructProperty::VECTOR,
+            StructDataType::Quat(_) => StructProperty::QUAT,
+            StructDataType::InventoryItem(_) => StructProperty::INVENTORY_ITEM,
+            StructDataType::FluidBox(_) => StructProperty::FLUID_BOX,
+            StructDataType::Other { name, .. } => name,
+        }
+    }
 
-                (size, name.into(), Box::new(write))
-            }
-            StructDataType::Other { name, list } => {
-                let size = list.size();
-                let write = |writer: &mut W| list.bp_write(writer);
+    /// Size of the struct data alone, without the struct name
+    pub fn size(&self) -> u32 {
+        match self {
+            StructDataType::LinearColor(_) => 16,
+            StructDataType::Vector(_) => 24,
+            StructDataType::Quat(_) => 32,
+            StructDataType::InventoryItem(item) => item.size(),
+            StructDataType::FluidBox(_) => 4,
+            StructDataType::Other { list, .. } => list.size(),
+        }
+    }
+
+    /// Calls `f` with `name` and this struct, or with every struct nested inside it. Nested structs
+    /// are passed with their own property name.
+    pub fn for_each_struct_mut(&mut self, name: &str, f: &mut impl FnMut(&str, &mut Self)) {
+        match self {
+            StructDataType::Other { list, .. } => list.for_each_struct_mut(f),
+            _ => f(name, self),
+        }
+    }
+
+    /// Prunes the references nested in this struct, see [`PropertyList::prune_references`].
+    pub fn prune_references(&mut self, dangling: &impl Fn(&str) -> bool) -> Vec<String> {
+        match self {
+            StructDataType::Other { list, .. } => list.prune_references(dangling),
+            _ => Vec::new(),
+        }
+    }
 
-                (size, name, Box::new(write))
+    /// Calls `f` with the path name of every object referenced by this struct.
+    pub fn for_each_path_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            StructDataType::InventoryItem(item) => f(&mut item.item.path_name),
+            StructDataType::Other { list, .. } => list.for_each_path_name_mut(f),
+            _ => {}
+        }
+    }
+}
+
+impl<W: Write> BPWrite<W> for &StructDataType {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        match self {
+            StructDataType::LinearColor(linear_color) => linear_color.bp_write(writer),
+            StructDataType::Vector(vector) => {
+                vector.x.bp_write(writer)?;
+                vector.y.bp_write(writer)?;
+                vector.z.bp_write(writer)
             }
+            StructDataType::Quat(quat) => {
+                quat.x.bp_write(writer)?;
+                quat.y.bp_write(writer)?;
+                quat.z.bp_write(writer)?;
+                quat.w.bp_write(writer)
+            }
+            StructDataType::InventoryItem(item) => item.bp_write(writer),
+            StructDataType::FluidBox(fluid_box) => fluid_box.content.bp_write(writer),
+            StructDataType::Other { list, .. } => list.bp_write(writer),
         }
     }
 }
 
-// impl StructDataType<'_> {
-//     const LC: FString<'static> = FString::new("LinearColor\0");
-//
-//     pub fn size(&self) -> u32 {
-//         match self {
-//             StructDataType::LinearColor(_) => 16,
-//             StructDataType::Other { name, list } => name.size() + list.size() + 16,
-//         }
-//     }
-// }
-
-// impl<W: Write> BPWrite<W> for &StructDataType<'_> {
-//     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
-//         match self {
-//             StructDataType::LinearColor(linear_color) => {
-//                 StructDataType::LC.bp_write(writer)?;
-//                 [0; 17].bp_write(writer)?;
-//                 linear_color.bp_write(writer)?;
-//             }
-//             StructDataType::Other { name, list } => {
-//                 name.bp_write(writer)?;
-//                 [0; 17].bp_write(writer)?;
-//                 list.bp_write(writer)?;
-//             }
-//         }
-//
-//         Ok(())
-//     }
-// }
+/// Parses the data of a struct whose type name has already been read, either from a struct
+/// property or from the inner type of a struct array.
+pub fn struct_data(name: &str, data: &mut &Bytes) -> winnow::Result<StructDataType> {
+    match name {
+        StructProperty::LC => linear_color
+            .map(StructDataType::LinearColor)
+            .context(StrContext::Label("linear color data"))
+            .parse_next(data),
+        StructProperty::VECTOR => (le_f64, le_f64, le_f64)
+            .map(|(x, y, z)| StructDataType::Vector(Vec3::new(x, y, z)))
+            .context(StrContext::Label("vector data"))
+            .parse_next(data),
+        StructProperty::QUAT => (le_f64, le_f64, le_f64, le_f64)
+            .map(|(x, y, z, w)| StructDataType::Quat(Quat::new(x, y, z, w)))
+            .context(StrContext::Label("quat data"))
+            .parse_next(data),
+        StructProperty::INVENTORY_ITEM => inventory_item
+            .map(StructDataType::InventoryItem)
+            .context(StrContext::Label("inventory item data"))
+            .parse_next(data),
+        StructProperty::FLUID_BOX => le_f32
+            .map(|content| StructDataType::FluidBox(FluidBox { content }))
+            .context(StrContext::Label("fluid box data"))
+            .parse_next(data),
+        name => property_list
+            .map(|list| StructDataType::Other {
+                name: name.into(),
+                list,
+            })
+            .context(StrContext::Label("property list data"))
+            .parse_next(data),
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct LinearColor {
@@ -94,6 +153,79 @@ impl<W: Write> BPWrite<W> for &LinearColor {
     }
 }
 
+/// Converts a linear channel to sRGB, both between 0 and 1.
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts an sRGB channel to linear, both between 0 and 1.
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+impl LinearColor {
+    /// Converts 8 bit sRGB channels with a linear alpha, as shown by colour pickers.
+    pub fn from_srgb8([r, g, b, a]: [u8; 4]) -> Self {
+        let channel = |value: u8| srgb_to_linear(f32::from(value) / 255.0);
+
+        LinearColor {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+            a: f32::from(a) / 255.0,
+        }
+    }
+
+    /// Converts to 8 bit sRGB channels with a linear alpha. Values outside 0 to 1 are clamped.
+    pub fn to_srgb8(&self) -> [u8; 4] {
+        let byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        [
+            byte(linear_to_srgb(self.r)),
+            byte(linear_to_srgb(self.g)),
+            byte(linear_to_srgb(self.b)),
+            byte(self.a),
+        ]
+    }
+
+    /// Parses an sRGB hex colour, `#RRGGBB` or `#RRGGBBAA` with an optional `#`.
+    pub fn from_hex(hex: &str) -> color_eyre::Result<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            color_eyre::eyre::bail!("Invalid hex colour {hex:?}");
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16);
+        let alpha = if digits.len() == 8 {
+            channel(3)?
+        } else {
+            u8::MAX
+        };
+
+        Ok(Self::from_srgb8([
+            channel(0)?,
+            channel(1)?,
+            channel(2)?,
+            alpha,
+        ]))
+    }
+
+    /// Formats as an sRGB hex colour `#RRGGBB`, leaving out the alpha.
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, _] = self.to_srgb8();
+        format!("#{r:02X}{g:02X}{b:02X}")
+    }
+}
+
 pub fn linear_color(data: &mut &Bytes) -> winnow::Result<LinearColor> {
     seq! {LinearColor {
         r: le_f32.context(StrContext::Label("red")),
@@ -104,6 +236,41 @@ pub fn linear_color(data: &mut &Bytes) -> winnow::Result<LinearColor> {
     .parse_next(data)
 }
 
+// Blueprints never contain items so only empty slots have been seen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryItem {
+    pub unknown: u32,
+    pub item: ObjectRef,
+}
+
+impl InventoryItem {
+    pub fn size(&self) -> u32 {
+        self.item.size() + 4
+    }
+}
+
+impl<W: Write> BPWrite<W> for &InventoryItem {
+    fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
+        self.unknown.bp_write(writer)?;
+        self.item.bp_write(writer)
+    }
+}
+
+pub fn inventory_item(data: &mut &Bytes) -> winnow::Result<InventoryItem> {
+    seq! {InventoryItem {
+        unknown: le_u32.context(StrContext::Label("unknown")),
+        item: object_ref.context(StrContext::Label("item")),
+    }}
+    .parse_next(data)
+}
+
+/// Fluid held by a pipe or a fluid buffer, saved without the properties of other structs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FluidBox {
+    /// Volume of fluid in cubic metres
+    pub content: f32,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructProperty {
     pub index: u32,
@@ -111,39 +278,40 @@ pub struct StructProperty {
 }
 
 impl StructProperty {
-    const LC: &'static str = "LinearColor\0";
+    pub const LC: &'static str = "LinearColor\0";
+    pub const VECTOR: &'static str = "Vector\0";
+    pub const QUAT: &'static str = "Quat\0";
+    pub const INVENTORY_ITEM: &'static str = "InventoryItem\0";
+    pub const FLUID_BOX: &'static str = "FluidBox\0";
 
     pub fn size(&self) -> u32 {
-        let data_size = match &self.data {
-            StructDataType::LinearColor(_) => 16 + Self::LC.size(),
-            StructDataType::Other { name, list } => name.size() + list.size(),
-        };
-
-        data_size + 25
+        self.data.name().size() + self.data.size() + 25
     }
 }
 
 impl<W: Write> BPWrite<W> for &StructProperty {
     fn bp_write(self, writer: &mut W) -> Result<(), std::io::Error> {
-        let (data_size, data_name, write_data) = self.data.get_writable();
-
-        data_size.bp_write(writer)?;
+        self.data.size().bp_write(writer)?;
         self.index.bp_write(writer)?;
-        data_name.bp_write(writer)?;
+        self.data.name().bp_write(writer)?;
         [0u8; 17].bp_write(writer)?;
-        write_data(writer)
+        self.data.bp_write(writer)
     }
 }
 
-pub fn struct_property<'d>(data: &mut &'d Bytes) -> winnow::Result<StructProperty> {
-    seq! {StructProperty {
-        _: le_u32,
-        index: le_u32.context(StrContext::Label("struct property index")),
-        data: dispatch! {terminated(fstring, &[0; 17]).map(AsRef::as_ref);
-            StructProperty::LC => linear_color.map(StructDataType::LinearColor).context(StrContext::Label("linear color data")),
-            name => property_list.map(|list| StructDataType::Other { name: name.into(), list }).context(StrContext::Label("property list data")),
-        },
-    }}.parse_next(data)
+pub fn struct_property(data: &mut &Bytes) -> winnow::Result<StructProperty> {
+    let index = (
+        le_u32,
+        le_u32.context(StrContext::Label("struct property index")),
+    )
+        .map(|(_, index)| index)
+        .parse_next(data)?;
+    let name = terminated(fstring, &[0; 17])
+        .context(StrContext::Label("struct name"))
+        .parse_next(data)?;
+    let data = struct_data(name, data)?;
+
+    Ok(StructProperty { index, data })
 }
 
 #[cfg(test)]
@@ -167,6 +335,38 @@ mod tests {
         assert_eq!(color.a.floor(), 1.0);
     }
 
+    #[test]
+    fn check_color_conversion() {
+        let white = LinearColor::from_hex("#FFFFFF").expect("Hex should parse");
+        assert_eq!(
+            white,
+            LinearColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0
+            }
+        );
+
+        // sRGB mid grey is much darker in linear space
+        let grey = LinearColor::from_hex("808080").expect("Hex should parse");
+        assert!((grey.r - 0.2158605).abs() < 1e-6);
+        assert_eq!(grey.to_hex(), "#808080");
+
+        let color = LinearColor::from_hex("#FA9549C0").expect("Hex should parse");
+        assert_eq!(color.to_srgb8(), [0xFA, 0x95, 0x49, 0xC0]);
+        assert_eq!(color.to_hex(), "#FA9549");
+
+        for value in 0..=u8::MAX {
+            let color = LinearColor::from_srgb8([value, value, value, value]);
+            assert_eq!(color.to_srgb8(), [value; 4]);
+        }
+
+        assert!(LinearColor::from_hex("#FFF").is_err());
+        assert!(LinearColor::from_hex("#GGGGGG").is_err());
+        assert!(LinearColor::from_hex("+FFFFFF").is_err());
+    }
+
     #[test]
     fn check_struct_color() {
         const DATA: [u8; 0x39] = [
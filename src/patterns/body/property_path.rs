@@ -0,0 +1,797 @@
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+
+use crate::{
+    patterns::body::{
+        ActorObject, ArrayProperty, ArrayValue, BoolProperty, ByteProperty, ByteType,
+        FloatProperty, FluidBox, IntProperty, LEVEL_NAME, LEVEL_PATH, LinearColor, ObjectProperty,
+        ObjectRef, Property, PropertyList, PropertyType, StructDataType, StructProperty,
+    },
+    transform::{Quat, Vec3},
+};
+
+/// One step of a property path, a property name optionally followed by an index in brackets.
+/// The index selects an element of an array property, or the property of that index among the
+/// properties sharing the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment<'a> {
+    name: &'a str,
+    index: Option<usize>,
+}
+
+fn segment(text: &str) -> Result<Segment<'_>> {
+    let (name, index) = match text.split_once('[') {
+        Some((name, index)) => {
+            let index = index
+                .strip_suffix(']')
+                .and_then(|index| index.parse().ok())
+                .ok_or_else(|| eyre!("Invalid index in property path segment {text:?}"))?;
+
+            (name, Some(index))
+        }
+        None => (text, None),
+    };
+    if name.is_empty() || name.contains(']') {
+        bail!("Invalid property name in property path segment {text:?}");
+    }
+
+    Ok(Segment { name, index })
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>> {
+    path.split('.').map(segment).collect()
+}
+
+/// Level name of a reference, objects of the level are referenced with it and assets without
+fn level_name(reference: &str) -> String {
+    if reference.starts_with(LEVEL_PATH) {
+        LEVEL_NAME.to_owned()
+    } else {
+        String::new()
+    }
+}
+
+/// Element of an array property, which has no property of its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Element<'p> {
+    Byte(u8),
+    Int(i32),
+    Object(&'p ObjectRef),
+    Struct(&'p StructDataType),
+}
+
+/// Value of a property converted to a Rust type.
+pub trait FromProperty<'p>: Sized {
+    fn from_property(property: &'p PropertyType) -> Option<Self>;
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        let _ = element;
+        None
+    }
+}
+
+impl FromProperty<'_> for bool {
+    fn from_property(property: &PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::BoolProperty(bool_property) => Some(bool_property.value),
+            _ => None,
+        }
+    }
+}
+
+impl FromProperty<'_> for u8 {
+    fn from_property(property: &PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::ByteProperty(byte_property) => match byte_property.value() {
+                ByteType::Byte(value) => Some(*value),
+                ByteType::FString(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn from_element(element: Element) -> Option<Self> {
+        match element {
+            Element::Byte(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromProperty<'_> for i32 {
+    fn from_property(property: &PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::IntProperty(int_property) => Some(int_property.value),
+            _ => None,
+        }
+    }
+
+    fn from_element(element: Element) -> Option<Self> {
+        match element {
+            Element::Int(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromProperty<'_> for f32 {
+    fn from_property(property: &PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::FloatProperty(float_property) => Some(float_property.value),
+            _ => None,
+        }
+    }
+}
+
+/// The reference of object properties, the value of enum properties and the text of string
+/// properties
+impl<'p> FromProperty<'p> for &'p str {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::ObjectProperty(object_property) => Some(&object_property.reference),
+            PropertyType::EnumProperty(enum_property) => Some(&enum_property.value),
+            PropertyType::StrProperty(str_property) => Some(&str_property.value),
+            _ => None,
+        }
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match element {
+            Element::Object(object) => Some(&object.path_name),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for &'p StructDataType {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::StructProperty(struct_property) => Some(&struct_property.data),
+            _ => None,
+        }
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match element {
+            Element::Struct(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// The properties of structs that are not one of the known struct types
+impl<'p> FromProperty<'p> for &'p PropertyList {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        Self::from_element(Element::Struct(<&StructDataType>::from_property(property)?))
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match <&StructDataType>::from_element(element)? {
+            StructDataType::Other { list, .. } => Some(list),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for LinearColor {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        Self::from_element(Element::Struct(<&StructDataType>::from_property(property)?))
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match <&StructDataType>::from_element(element)? {
+            StructDataType::LinearColor(color) => Some(*color),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for Vec3 {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        Self::from_element(Element::Struct(<&StructDataType>::from_property(property)?))
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match <&StructDataType>::from_element(element)? {
+            StructDataType::Vector(vector) => Some(*vector),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for Quat {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        Self::from_element(Element::Struct(<&StructDataType>::from_property(property)?))
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match <&StructDataType>::from_element(element)? {
+            StructDataType::Quat(quat) => Some(*quat),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for FluidBox {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        Self::from_element(Element::Struct(<&StructDataType>::from_property(property)?))
+    }
+
+    fn from_element(element: Element<'p>) -> Option<Self> {
+        match <&StructDataType>::from_element(element)? {
+            StructDataType::FluidBox(fluid_box) => Some(*fluid_box),
+            _ => None,
+        }
+    }
+}
+
+impl<'p> FromProperty<'p> for &'p ArrayValue {
+    fn from_property(property: &'p PropertyType) -> Option<Self> {
+        match property {
+            PropertyType::ArrayProperty(array_property) => Some(&array_property.value),
+            _ => None,
+        }
+    }
+}
+
+/// Value stored with [`PropertyList::set`] and [`PropertyList::insert`]. Strings include the
+/// trailing nul like the rest of the crate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Replaces a property whatever its type
+    Property(PropertyType),
+    Bool(bool),
+    Byte(u8),
+    Int(i32),
+    Float(f32),
+    /// An object reference, the value of an enum or the text of a string property
+    String(String),
+    Struct(StructDataType),
+    Array(ArrayValue),
+}
+
+impl Value {
+    /// A new property holding the value. Strings become object properties, enums can only be
+    /// created as a [`Value::Property`].
+    pub fn into_property(self, index: u32) -> PropertyType {
+        match self {
+            Value::Property(property) => property,
+            Value::Bool(value) => PropertyType::BoolProperty(BoolProperty { index, value }),
+            Value::Byte(value) => {
+                PropertyType::ByteProperty(ByteProperty::new(index, ByteType::Byte(value)))
+            }
+            Value::Int(value) => PropertyType::IntProperty(IntProperty { index, value }),
+            Value::Float(value) => PropertyType::FloatProperty(FloatProperty { index, value }),
+            Value::String(reference) => PropertyType::ObjectProperty(ObjectProperty {
+                index,
+                level_name: level_name(&reference),
+                reference,
+            }),
+            Value::Struct(data) => PropertyType::StructProperty(StructProperty { index, data }),
+            Value::Array(value) => PropertyType::ArrayProperty(ArrayProperty { index, value }),
+        }
+    }
+}
+
+impl From<PropertyType> for Value {
+    fn from(property: PropertyType) -> Self {
+        Value::Property(property)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<u8> for Value {
+    fn from(value: u8) -> Self {
+        Value::Byte(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<StructDataType> for Value {
+    fn from(data: StructDataType) -> Self {
+        Value::Struct(data)
+    }
+}
+
+impl From<LinearColor> for Value {
+    fn from(color: LinearColor) -> Self {
+        Value::Struct(StructDataType::LinearColor(color))
+    }
+}
+
+impl From<Vec3> for Value {
+    fn from(vector: Vec3) -> Self {
+        Value::Struct(StructDataType::Vector(vector))
+    }
+}
+
+impl From<Quat> for Value {
+    fn from(quat: Quat) -> Self {
+        Value::Struct(StructDataType::Quat(quat))
+    }
+}
+
+impl From<FluidBox> for Value {
+    fn from(fluid_box: FluidBox) -> Self {
+        Value::Struct(StructDataType::FluidBox(fluid_box))
+    }
+}
+
+impl From<ArrayValue> for Value {
+    fn from(value: ArrayValue) -> Self {
+        Value::Array(value)
+    }
+}
+
+impl PropertyType {
+    /// Replaces the value of the property, which must be of a matching type. Structs keep their
+    /// struct type.
+    pub fn set(&mut self, value: Value) -> Result<()> {
+        match (self, value) {
+            (property, Value::Property(value)) => *property = value,
+            (PropertyType::BoolProperty(property), Value::Bool(value)) => property.value = value,
+            (PropertyType::ByteProperty(property), Value::Byte(value)) => {
+                property.set_value(ByteType::Byte(value))
+            }
+            (PropertyType::IntProperty(property), Value::Int(value)) => property.value = value,
+            (PropertyType::FloatProperty(property), Value::Float(value)) => property.value = value,
+            (PropertyType::ObjectProperty(property), Value::String(reference)) => {
+                property.level_name = level_name(&reference);
+                property.reference = reference;
+            }
+            (PropertyType::EnumProperty(property), Value::String(value)) => property.value = value,
+            (PropertyType::StrProperty(property), Value::String(value)) => property.value = value,
+            (PropertyType::StructProperty(property), Value::Struct(data))
+                if property.data.name() == data.name() =>
+            {
+                property.data = data
+            }
+            (PropertyType::ArrayProperty(property), Value::Array(value)) => property.value = value,
+            (property, value) => bail!("Cannot store {value:?} in {property:?}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl ArrayValue {
+    pub fn get(&self, index: usize) -> Option<Element<'_>> {
+        match self {
+            ArrayValue::Byte(values) => values.get(index).copied().map(Element::Byte),
+            ArrayValue::Int(values) => values.get(index).copied().map(Element::Int),
+            ArrayValue::Object(values) => values.get(index).map(Element::Object),
+            ArrayValue::Struct { elements, .. } => elements.get(index).map(Element::Struct),
+        }
+    }
+
+    /// Stores `value` at `index`, either replacing the element there or inserting before it.
+    fn put(&mut self, index: usize, value: Value, replace: bool) -> Result<()> {
+        fn put<T>(values: &mut Vec<T>, index: usize, value: T, replace: bool) {
+            if replace {
+                values[index] = value;
+            } else {
+                values.insert(index, value);
+            }
+        }
+
+        let len = self.len();
+        if index > len || (replace && index == len) {
+            bail!("Index {index} is out of bounds for an array of {len} elements");
+        }
+
+        match (self, value) {
+            (ArrayValue::Byte(values), Value::Byte(value)) => put(values, index, value, replace),
+            (ArrayValue::Int(values), Value::Int(value)) => put(values, index, value, replace),
+            (ArrayValue::Object(values), Value::String(path_name)) => {
+                let object = ObjectRef {
+                    level_name: level_name(&path_name),
+                    path_name,
+                };
+                put(values, index, object, replace)
+            }
+            (
+                ArrayValue::Struct {
+                    struct_name,
+                    elements,
+                    ..
+                },
+                Value::Struct(data),
+            ) if *struct_name == data.name() => put(elements, index, data, replace),
+            (array, value) => bail!(
+                "Cannot store {value:?} in an array of {}",
+                array.inner_type().trim_end_matches('\0')
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the element at `index`.
+    pub fn set(&mut self, index: usize, value: Value) -> Result<()> {
+        self.put(index, value, true)
+    }
+
+    /// Inserts an element at `index`, shifting the elements after it.
+    pub fn insert(&mut self, index: usize, value: Value) -> Result<()> {
+        self.put(index, value, false)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Value> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(match self {
+            ArrayValue::Byte(values) => Value::Byte(values.remove(index)),
+            ArrayValue::Int(values) => Value::Int(values.remove(index)),
+            ArrayValue::Object(values) => Value::String(values.remove(index).path_name),
+            ArrayValue::Struct { elements, .. } => Value::Struct(elements.remove(index)),
+        })
+    }
+}
+
+/// What a path resolves to in the last list on the path
+enum Target<'p> {
+    Property(&'p Property),
+    Element(Element<'p>),
+}
+
+impl PropertyList {
+    /// Position of the property a segment names. Array properties are returned whatever the
+    /// index, which then selects one of their elements.
+    fn position(&self, segment: &Segment) -> Option<usize> {
+        let named = |prop: &Property| prop.name.trim_end_matches('\0') == segment.name;
+        let first = self.0.iter().position(named)?;
+
+        match segment.index {
+            Some(index) if !matches!(self.0[first].property, PropertyType::ArrayProperty(_)) => {
+                self.0
+                    .iter()
+                    .position(|prop| named(prop) && prop.property.index() as usize == index)
+            }
+            _ => Some(first),
+        }
+    }
+
+    fn target(&self, segment: &Segment) -> Option<Target<'_>> {
+        let property = &self.0[self.position(segment)?];
+
+        match (&property.property, segment.index) {
+            (PropertyType::ArrayProperty(array_property), Some(index)) => {
+                array_property.value.get(index).map(Target::Element)
+            }
+            _ => Some(Target::Property(property)),
+        }
+    }
+
+    fn child(&self, segment: &Segment) -> Option<&PropertyList> {
+        match self.target(segment)? {
+            Target::Property(property) => <&PropertyList>::from_property(&property.property),
+            Target::Element(element) => <&PropertyList>::from_element(element),
+        }
+    }
+
+    fn child_mut(&mut self, segment: &Segment) -> Option<&mut PropertyList> {
+        let position = self.position(segment)?;
+
+        let data = match (&mut self.0[position].property, segment.index) {
+            (PropertyType::ArrayProperty(array_property), Some(index)) => {
+                match &mut array_property.value {
+                    ArrayValue::Struct { elements, .. } => elements.get_mut(index)?,
+                    _ => return None,
+                }
+            }
+            (PropertyType::StructProperty(struct_property), _) => &mut struct_property.data,
+            _ => return None,
+        };
+
+        match data {
+            StructDataType::Other { list, .. } => Some(list),
+            _ => None,
+        }
+    }
+
+    /// The list holding the last segment of `path`, and that segment.
+    fn parent<'a>(&self, path: &'a str) -> Option<(&PropertyList, Segment<'a>)> {
+        let segments = parse_path(path).ok()?;
+        let (last, parents) = segments.split_last()?;
+
+        let mut list = self;
+        for segment in parents {
+            list = list.child(segment)?;
+        }
+
+        Some((list, *last))
+    }
+
+    fn parent_mut<'a>(&mut self, path: &'a str) -> Result<(&mut PropertyList, Segment<'a>)> {
+        let segments = parse_path(path)?;
+        let (last, parents) = segments
+            .split_last()
+            .ok_or_else(|| eyre!("Empty property path"))?;
+
+        let mut list = self;
+        for segment in parents {
+            list = list.child_mut(segment).ok_or_else(|| {
+                eyre!("No struct at {:?} of property path {path:?}", segment.name)
+            })?;
+        }
+
+        Ok((list, *last))
+    }
+
+    /// Finds a property by a path of names separated by dots, such as
+    /// `mCustomizationData.SwatchDesc`, descending into structs. Indices in brackets select an
+    /// element of an array, as in `mSplineData[1].Location`, or one of the properties sharing a
+    /// name, as in `Locations[1]`. Names are given without the trailing nul.
+    pub fn find_path(&self, path: &str) -> Option<&Property> {
+        let (list, last) = self.parent(path)?;
+
+        match list.target(&last)? {
+            Target::Property(property) => Some(property),
+            Target::Element(_) => None,
+        }
+    }
+
+    /// Value of the property or array element at `path`, if there is one of type `T`. See
+    /// [`Self::find_path`].
+    pub fn get<'p, T: FromProperty<'p>>(&'p self, path: &str) -> Option<T> {
+        let (list, last) = self.parent(path)?;
+
+        match list.target(&last)? {
+            Target::Property(property) => T::from_property(&property.property),
+            Target::Element(element) => T::from_element(element),
+        }
+    }
+
+    /// Replaces the value of the existing property or array element at `path`.
+    pub fn set(&mut self, path: &str, value: impl Into<Value>) -> Result<()> {
+        let (list, last) = self.parent_mut(path)?;
+        let position = list
+            .position(&last)
+            .ok_or_else(|| eyre!("No property at {path:?}"))?;
+
+        match (&mut list.0[position].property, last.index) {
+            (PropertyType::ArrayProperty(array_property), Some(index)) => {
+                array_property.value.set(index, value.into())
+            }
+            (property, _) => property.set(value.into()),
+        }
+    }
+
+    /// Adds a property at `path`, after the other properties of the same name. An index on an
+    /// array property inserts an element into the array instead.
+    pub fn insert(&mut self, path: &str, value: impl Into<Value>) -> Result<()> {
+        let (list, last) = self.parent_mut(path)?;
+
+        if let Some(position) = list.position(&last) {
+            return match (&mut list.0[position].property, last.index) {
+                (PropertyType::ArrayProperty(array_property), Some(index)) => {
+                    array_property.value.insert(index, value.into())
+                }
+                _ => bail!("Property {path:?} already exists"),
+            };
+        }
+
+        let index = last.index.unwrap_or(0) as u32;
+        let property = Property {
+            name: format!("{}\0", last.name),
+            property: value.into().into_property(index),
+        };
+        let position = list
+            .0
+            .iter()
+            .rposition(|prop| prop.name == property.name)
+            .map_or(list.0.len(), |position| position + 1);
+        list.0.insert(position, property);
+
+        Ok(())
+    }
+
+    /// Removes the property or array element at `path`, returning its value.
+    pub fn remove(&mut self, path: &str) -> Option<Value> {
+        let (list, last) = self.parent_mut(path).ok()?;
+        let position = list.position(&last)?;
+
+        match (&mut list.0[position].property, last.index) {
+            (PropertyType::ArrayProperty(array_property), Some(index)) => {
+                array_property.value.remove(index)
+            }
+            _ => Some(Value::Property(list.0.remove(position).property)),
+        }
+    }
+}
+
+impl ActorObject {
+    /// See [`PropertyList::get`].
+    pub fn get<'p, T: FromProperty<'p>>(&'p self, path: &str) -> Option<T> {
+        self.properties.get(path)
+    }
+
+    /// See [`PropertyList::set`].
+    pub fn set(&mut self, path: &str, value: impl Into<Value>) -> Result<()> {
+        self.properties.set(path, value)
+    }
+
+    /// See [`PropertyList::insert`].
+    pub fn insert(&mut self, path: &str, value: impl Into<Value>) -> Result<()> {
+        self.properties.insert(path, value)
+    }
+
+    /// See [`PropertyList::remove`].
+    pub fn remove(&mut self, path: &str) -> Option<Value> {
+        self.properties.remove(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{Blueprint, body::ObjectType},
+    };
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    fn actor_with<'b>(blueprint: &'b mut Blueprint, path: &str) -> &'b mut ActorObject {
+        blueprint
+            .body
+            .objects
+            .iter_mut()
+            .find_map(|object| match object {
+                ObjectType::Actor(actor) if actor.properties.find_path(path).is_some() => {
+                    Some(actor)
+                }
+                _ => None,
+            })
+            .expect("An actor should have the property")
+    }
+
+    #[test]
+    fn check_segment() {
+        assert_eq!(
+            segment("mSplineData[12]").expect("Segment should parse"),
+            Segment {
+                name: "mSplineData",
+                index: Some(12)
+            }
+        );
+        assert!(segment("").is_err());
+        assert!(segment("[1]").is_err());
+        assert!(segment("mSplineData[x]").is_err());
+        assert!(segment("mSplineData[1").is_err());
+    }
+
+    #[test]
+    fn check_get() {
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+
+        let wire = actor_with(&mut blueprint, "mWireInstances");
+        let first: Vec3 = wire
+            .get("mWireInstances[0].Locations")
+            .expect("Wire should have a first location");
+        let second: Vec3 = wire
+            .get("mWireInstances[0].Locations[1]")
+            .expect("Wire should have a second location");
+        assert_ne!(first, second);
+        assert_eq!(wire.get::<Vec3>("mWireInstances[0].Locations[2]"), None);
+        assert_eq!(wire.get::<Vec3>("mWireInstances[1].Locations"), None);
+        assert_eq!(wire.get::<Vec3>("mWireInstances.Locations"), None);
+
+        let connection = blueprint
+            .body
+            .query()
+            .components()
+            .has_property("mWires")
+            .iter()
+            .next()
+            .expect("A power connection should have wires");
+        let power_line: &str = connection
+            .object
+            .properties()
+            .get("mWires[0]")
+            .expect("Connection should have a wire");
+        assert!(power_line.contains("Build_PowerLine_C"));
+    }
+
+    #[test]
+    fn check_edit() {
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        const SWATCH: &str = "/Game/FactoryGame/Buildable/-Shared/Customization/Swatches/SwatchDesc_Slot3.SwatchDesc_Slot3_C\0";
+
+        let actor = actor_with(&mut blueprint, "mSplineData");
+        actor
+            .set("mCustomizationData.SwatchDesc", SWATCH)
+            .expect("Set should succeed");
+        assert_eq!(actor.get("mCustomizationData.SwatchDesc"), Some(SWATCH));
+        assert!(actor.set("mCustomizationData.SwatchDesc", 1.0f32).is_err());
+        assert!(actor.set("mCustomizationData.Missing", 1.0f32).is_err());
+
+        let location = Vec3::new(1.0, 2.0, 3.0);
+        actor
+            .set("mSplineData[1].Location", location)
+            .expect("Set should succeed");
+        assert_eq!(actor.get("mSplineData[1].Location"), Some(location));
+        assert!(
+            actor
+                .set("mSplineData[1].Location", Quat::IDENTITY)
+                .is_err()
+        );
+
+        let points = actor
+            .get::<&ArrayValue>("mSplineData")
+            .expect("Actor should have a spline")
+            .len();
+        let first = actor
+            .remove("mSplineData[0]")
+            .expect("Remove should succeed");
+        actor
+            .insert("mSplineData[1]", first.clone())
+            .expect("Insert should succeed");
+        assert_eq!(
+            actor.get::<&ArrayValue>("mSplineData").map(ArrayValue::len),
+            Some(points)
+        );
+        assert!(actor.insert("mSplineData[9]", first).is_err());
+
+        actor
+            .insert("mTestFloat", 4.0f32)
+            .expect("Insert should succeed");
+        assert!(actor.insert("mTestFloat", 5.0f32).is_err());
+        actor
+            .insert("mTestFloat[1]", 5.0f32)
+            .expect("Insert should succeed");
+        assert_eq!(actor.get("mTestFloat[1]"), Some(5.0f32));
+        assert_eq!(
+            actor.remove("mTestFloat"),
+            Some(Value::Property(PropertyType::FloatProperty(
+                FloatProperty {
+                    index: 0,
+                    value: 4.0
+                }
+            )))
+        );
+        assert_eq!(actor.get("mTestFloat[1]"), Some(5.0f32));
+
+        // Sizes of the edited lists are recomputed when writing
+        let body = blueprint.body.clone();
+        let mut buf = Cursor::new(Vec::new());
+        blueprint.bp_write(&mut buf).expect("Write should succeed");
+
+        let parsed = Blueprint::new(buf.get_ref().as_slice()).expect("Parse should succeed");
+        assert_eq!(parsed.body, body);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::patterns::body::{
+    BlueprintBody, Entity, FromProperty, ObjectHeaderType, Property, PropertyType,
+};
+
+/// Matches `text` against a glob `pattern` where `*` matches any run of characters and `?` any
+/// single character. Trailing nuls are ignored on both sides.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.trim_end_matches('\0').chars().collect();
+    let text: Vec<char> = text.trim_end_matches('\0').chars().collect();
+
+    // Position after the last star and the text position it is currently matched up to
+    let mut star = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+type Filter<'b> = Box<dyn Fn(&Entity<'b>) -> bool + 'b>;
+
+/// Filters the objects of a body. Created by [`BlueprintBody::query`], every filter added must
+/// match for an object to be returned.
+pub struct Query<'b> {
+    body: &'b BlueprintBody,
+    filters: Vec<Filter<'b>>,
+}
+
+impl<'b> Query<'b> {
+    /// Keeps the objects matching `filter`.
+    pub fn filter(mut self, filter: impl Fn(&Entity<'b>) -> bool + 'b) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Keeps actors, leaving out components.
+    pub fn actors(self) -> Self {
+        self.filter(|entity| matches!(entity.header, ObjectHeaderType::Actor(_)))
+    }
+
+    /// Keeps components, leaving out actors.
+    pub fn components(self) -> Self {
+        self.filter(|entity| matches!(entity.header, ObjectHeaderType::Component(_)))
+    }
+
+    /// Keeps the objects whose type path matches the glob `pattern`, see [`glob_match`].
+    pub fn type_path(self, pattern: &str) -> Self {
+        let pattern = pattern.to_owned();
+        self.filter(move |entity| glob_match(&pattern, entity.header.type_path()))
+    }
+
+    /// Keeps the objects that have a property at `path`.
+    pub fn has_property(self, path: &str) -> Self {
+        let path = path.to_owned();
+        self.filter(move |entity| entity.object.properties().find_path(&path).is_some())
+    }
+
+    /// Keeps the objects with an object property at `path` referencing something matching the glob
+    /// `pattern`, for example `query().reference("mBuiltWithRecipe", "*Recipe_Beam*")`.
+    pub fn reference(self, path: &str, pattern: &str) -> Self {
+        let (path, pattern) = (path.to_owned(), pattern.to_owned());
+        self.filter(move |entity| {
+            matches!(
+                entity.object.properties().find_path(&path),
+                Some(Property {
+                    property: PropertyType::ObjectProperty(object_property),
+                    ..
+                }) if glob_match(&pattern, &object_property.reference)
+            )
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity<'b>> {
+        self.body
+            .entities()
+            .filter(|entity| self.filters.iter().all(|filter| filter(entity)))
+    }
+
+    /// The objects matching the query together with their value of type `T` at `path`. Objects
+    /// without such a value are skipped.
+    pub fn values<T: FromProperty<'b>>(&self, path: &str) -> impl Iterator<Item = (Entity<'b>, T)> {
+        self.iter().filter_map(move |entity| {
+            let value = entity.object.properties().get(path)?;
+            Some((entity, value))
+        })
+    }
+}
+
+impl BlueprintBody {
+    /// Starts a query matching every object of the body.
+    pub fn query(&self) -> Query<'_> {
+        Query {
+            body: self,
+            filters: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    #[test]
+    fn check_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("Build_*_C", "Build_GeneratorCoal_C\0"));
+        assert!(glob_match("*Coal*", "Build_GeneratorCoal_C"));
+        assert!(glob_match("Slot?", "Slot7"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("Slot?", "Slot17"));
+        assert!(!glob_match("Build_*_C", "Build_Beam"));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn check_query() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+
+        let generators = body.query().actors().type_path("*Build_GeneratorCoal_C");
+        assert_eq!(generators.iter().count(), 3);
+        assert_eq!(
+            body.query()
+                .reference("mBuiltWithRecipe", "*Recipe_GeneratorCoal*")
+                .iter()
+                .count(),
+            3
+        );
+
+        let swatches: Vec<&str> = generators
+            .values("mCustomizationData.SwatchDesc")
+            .map(|(_, swatch)| swatch)
+            .collect();
+        assert_eq!(swatches.len(), 3);
+        assert!(
+            swatches
+                .iter()
+                .all(|swatch| glob_match("*SwatchDesc_*", swatch))
+        );
+
+        // Values of the wrong type are skipped
+        assert_eq!(
+            generators
+                .values::<f32>("mCustomizationData.SwatchDesc")
+                .count(),
+            0
+        );
+        assert_eq!(
+            body.query()
+                .components()
+                .type_path("/Script/FactoryGame.FGPowerConnectionComponent")
+                .iter()
+                .count(),
+            body.query()
+                .components()
+                .type_path("*FGPowerConnectionComponent")
+                .iter()
+                .count()
+        );
+    }
+}
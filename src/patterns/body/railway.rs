@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+
+use crate::patterns::body::{
+    BlueprintBody, ConnectionGraph, ConnectionKind, ObjectHeaderType, ObjectType, Spline,
+    connected_components,
+};
+
+/// Type path of plain railway tracks, tracks of stations and platforms have their own
+pub const RAILROAD_TRACK: &str =
+    "/Game/FactoryGame/Buildable/Factory/Train/Track/Build_RailroadTrack.Build_RailroadTrack_C\0";
+/// Class of the connection components at the ends of a track
+pub const TRACK_CONNECTION: &str = "/Script/FactoryGame.FGRailroadTrackConnectionComponent\0";
+/// Index into the connected components of the track a switch is set to
+pub const SWITCH_POSITION: &str = "mSwitchPosition";
+/// Signal facing trains arriving at a track connection
+pub const FACING_SIGNAL: &str = "mFacingSignal";
+/// Signal behind trains leaving through a track connection
+pub const TRAILING_SIGNAL: &str = "mTrailingSignal";
+/// Station owning a track connection
+pub const STATION: &str = "mStation";
+
+/// A railway track connection component and the tracks it joins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackConnection {
+    /// Instance name of the connection component
+    pub component: String,
+    /// Instance name of the actor owning the component
+    pub track: String,
+    /// Instance names of the attached connections, more than one at a switch
+    pub connected: Vec<String>,
+    /// Index into `connected` of the track the switch is set to, 0 when it is not stored
+    pub switch_position: usize,
+    pub facing_signal: Option<String>,
+    pub trailing_signal: Option<String>,
+    pub station: Option<String>,
+}
+
+impl TrackConnection {
+    pub fn is_switch(&self) -> bool {
+        self.connected.len() > 1
+    }
+
+    /// Connection trains pass on to, following the switch position.
+    pub fn active(&self) -> Option<&str> {
+        self.connected
+            .get(self.switch_position)
+            .or_else(|| self.connected.first())
+            .map(String::as_str)
+    }
+}
+
+/// A railway track, an actor with a spline running between track connections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub instance_name: String,
+    pub type_path: String,
+    /// Spline in blueprint space
+    pub spline: Spline,
+    /// Connections at the ends of the track
+    pub connections: Vec<TrackConnection>,
+}
+
+impl BlueprintBody {
+    /// Every railway track connection component, of tracks as well as of stations and platforms.
+    pub fn track_connections(&self) -> Vec<TrackConnection> {
+        self.entities()
+            .filter_map(|entity| {
+                let (ObjectHeaderType::Component(header), ObjectType::Component(component)) =
+                    (entity.header, entity.object)
+                else {
+                    return None;
+                };
+                if ConnectionKind::from_class(&header.class_name) != Some(ConnectionKind::Rail) {
+                    return None;
+                }
+
+                let properties = &component.properties;
+                let reference = |name| {
+                    properties
+                        .get::<&str>(name)
+                        .filter(|target| !target.trim_end_matches('\0').is_empty())
+                        .map(ToOwned::to_owned)
+                };
+                Some(TrackConnection {
+                    component: header.instance_name.clone(),
+                    track: header.parent_actor.clone(),
+                    connected: connected_components(properties)
+                        .into_iter()
+                        .map(ToOwned::to_owned)
+                        .collect(),
+                    switch_position: properties
+                        .get::<i32>(SWITCH_POSITION)
+                        .map_or(0, |position| position.max(0) as usize),
+                    facing_signal: reference(FACING_SIGNAL),
+                    trailing_signal: reference(TRAILING_SIGNAL),
+                    station: reference(STATION),
+                })
+            })
+            .collect()
+    }
+
+    /// Every railway track with its spline and end connections.
+    pub fn tracks(&self) -> Vec<Track> {
+        let connections = self.track_connections();
+
+        self.entities()
+            .filter_map(|entity| {
+                let ObjectHeaderType::Actor(header) = entity.header else {
+                    return None;
+                };
+                let ends: Vec<_> = connections
+                    .iter()
+                    .filter(|connection| connection.track == header.instance_name)
+                    .cloned()
+                    .collect();
+                if ends.is_empty() {
+                    return None;
+                }
+
+                Some(Track {
+                    instance_name: header.instance_name.clone(),
+                    type_path: header.type_path.clone(),
+                    spline: entity.world_spline()?,
+                    connections: ends,
+                })
+            })
+            .collect()
+    }
+
+    /// Track connections joining more than one track.
+    pub fn switches(&self) -> Vec<TrackConnection> {
+        self.track_connections()
+            .into_iter()
+            .filter(TrackConnection::is_switch)
+            .collect()
+    }
+
+    /// The railway part of [`BlueprintBody::connections`], with the actors owning track
+    /// connections as nodes and only rail connections as edges.
+    pub fn track_graph(&self) -> ConnectionGraph {
+        let graph = self.connections();
+        let tracks: HashSet<_> = self
+            .track_connections()
+            .into_iter()
+            .map(|connection| connection.track)
+            .collect();
+
+        let mut nodes = Vec::new();
+        let mut indices = HashMap::new();
+        for (index, node) in graph.nodes.into_iter().enumerate() {
+            if tracks.contains(&node) {
+                indices.insert(index, nodes.len());
+                nodes.push(node);
+            }
+        }
+
+        ConnectionGraph {
+            nodes,
+            edges: graph
+                .edges
+                .into_iter()
+                .filter(|edge| edge.kind == ConnectionKind::Rail)
+                .filter_map(|mut edge| {
+                    edge.from = *indices.get(&edge.from)?;
+                    edge.to = *indices.get(&edge.to)?;
+                    Some(edge)
+                })
+                .collect(),
+            dangling: graph
+                .dangling
+                .into_iter()
+                .filter(|dangling| dangling.kind == ConnectionKind::Rail)
+                .collect(),
+        }
+    }
+
+    /// Sets the track a switch starts out set to, as an index into the components the track
+    /// connection `component` is attached to.
+    pub fn set_switch_position(&mut self, component: &str, position: usize) -> Result<()> {
+        let entity = self
+            .entity_mut(component)
+            .ok_or_else(|| eyre!("{component:?} is not in the body"))?;
+        let ObjectHeaderType::Component(header) = &*entity.header else {
+            bail!("{component:?} is not a track connection");
+        };
+        if ConnectionKind::from_class(&header.class_name) != Some(ConnectionKind::Rail) {
+            bail!("{component:?} is not a track connection");
+        }
+
+        let properties = entity.object.properties_mut();
+        let count = connected_components(properties).len();
+        if count < 2 {
+            bail!("Track connection {component:?} is not a switch");
+        }
+        if position >= count {
+            bail!("Switch {component:?} has {count} positions, cannot set it to {position}");
+        }
+
+        let position = position as i32;
+        if properties.find(SWITCH_POSITION).is_some() {
+            properties.set(SWITCH_POSITION, position)
+        } else {
+            properties.insert(SWITCH_POSITION, position)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{
+            Blueprint,
+            body::{
+                ActorHeader, ActorObject, ArrayValue, CONNECTED_COMPONENTS, ComponentHeader,
+                ComponentObject, LEVEL_NAME, ObjectRef, PropertyList, SplinePoint, blueprint_body,
+            },
+        },
+        transform::{Transform, Vec3},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    /// Adds a straight track from `start` to `end`, returning the names of its two connections.
+    fn add_track(body: &mut BlueprintBody, start: Vec3, end: Vec3) -> [String; 2] {
+        let instance_name = body.instance_names().allocate(RAILROAD_TRACK);
+        let base = instance_name.trim_end_matches('\0');
+        let connections = [0, 1].map(|i| format!("{base}.TrackConnection{i}\0"));
+
+        let direction = end - start;
+        let spline = Spline {
+            points: vec![
+                SplinePoint::new(Vec3::ZERO, direction),
+                SplinePoint::new(direction, direction),
+            ],
+        };
+        let mut actor = ActorObject::buildable(PropertyList(Vec::new()));
+        actor.set_spline(&spline).expect("Spline should be written");
+        let header = ActorHeader::new(
+            RAILROAD_TRACK,
+            instance_name.clone(),
+            &Transform::from_translation(start),
+        );
+        body.insert(ObjectHeaderType::Actor(header), ObjectType::Actor(actor))
+            .expect("Track should be inserted");
+
+        for name in &connections {
+            let header = ComponentHeader {
+                class_name: TRACK_CONNECTION.to_owned(),
+                root_object: LEVEL_NAME.to_owned(),
+                instance_name: name.clone(),
+                parent_actor: instance_name.clone(),
+            };
+            let component = ComponentObject {
+                properties: PropertyList(Vec::new()),
+                extra_data: vec![0; 4],
+            };
+            body.insert(
+                ObjectHeaderType::Component(header),
+                ObjectType::Component(component),
+            )
+            .expect("Track connection should be inserted");
+        }
+
+        connections
+    }
+
+    fn connect(body: &mut BlueprintBody, component: &str, targets: &[&str]) {
+        body.entity_mut(component)
+            .expect("Track connection should exist")
+            .object
+            .properties_mut()
+            .insert(
+                CONNECTED_COMPONENTS,
+                ArrayValue::Object(
+                    targets
+                        .iter()
+                        .map(|target| ObjectRef::level(target))
+                        .collect(),
+                ),
+            )
+            .expect("Insert should succeed");
+    }
+
+    /// A track splitting into two at a switch.
+    fn junction() -> (BlueprintBody, [[String; 2]; 3]) {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let main = add_track(&mut body, Vec3::ZERO, Vec3::new(1600.0, 0.0, 0.0));
+        let left = add_track(
+            &mut body,
+            Vec3::new(1600.0, 0.0, 0.0),
+            Vec3::new(3200.0, 400.0, 0.0),
+        );
+        let right = add_track(
+            &mut body,
+            Vec3::new(1600.0, 0.0, 0.0),
+            Vec3::new(3200.0, -400.0, 0.0),
+        );
+        connect(&mut body, &main[1], &[&left[0], &right[0]]);
+        connect(&mut body, &left[0], &[&main[1]]);
+        connect(&mut body, &right[0], &[&main[1]]);
+
+        (body, [main, left, right])
+    }
+
+    #[test]
+    fn check_tracks() {
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        assert_eq!(body.tracks(), []);
+        assert_eq!(body.track_graph().nodes.len(), 0);
+
+        let (body, [main, left, right]) = junction();
+        let tracks = body.tracks();
+        assert_eq!(tracks.len(), 3);
+        assert!((tracks[0].spline.length() - 1600.0).abs() < 1.0);
+        assert_eq!(
+            tracks[0].spline.points[1].location,
+            Vec3::new(1600.0, 0.0, 0.0)
+        );
+
+        let switches = body.switches();
+        assert_eq!(switches.len(), 1);
+        assert_eq!(switches[0].component, main[1]);
+        assert_eq!(switches[0].active(), Some(left[0].as_str()));
+
+        let graph = body.track_graph();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.dangling, []);
+        assert_eq!(graph.reachable(0, &[ConnectionKind::Rail]).len(), 3);
+        assert_eq!(graph.neighbors(0).count(), 2);
+        assert!(graph.edges.iter().any(|edge| edge.to_component == right[0]));
+    }
+
+    #[test]
+    fn check_switch_position() {
+        let (mut body, [main, _, right]) = junction();
+
+        body.set_switch_position(&main[1], 1)
+            .expect("Switch should be set");
+        let switch = body.switches().remove(0);
+        assert_eq!(switch.switch_position, 1);
+        assert_eq!(switch.active(), Some(right[0].as_str()));
+
+        assert!(body.set_switch_position(&main[1], 2).is_err());
+        assert!(body.set_switch_position(&main[0], 0).is_err());
+        assert!(body.set_switch_position("Missing\0", 0).is_err());
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+        assert_eq!(parsed.switches()[0].switch_position, 1);
+    }
+}
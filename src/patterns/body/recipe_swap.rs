@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::patterns::body::{BlueprintBody, LEVEL_PATH, class_name};
+
+/// Building types and recipes to replace with others, such as Mk1 belts with Mk5 belts. Paths
+/// include the trailing nul like the rest of the body.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RecipeSwap<'t> {
+    types: HashMap<&'t str, &'t str>,
+    recipes: HashMap<&'t str, &'t str>,
+}
+
+impl<'t> RecipeSwap<'t> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces buildings of type `from` with buildings of type `to`.
+    pub fn insert_type(&mut self, from: &'t str, to: &'t str) {
+        self.types.insert(from, to);
+    }
+
+    /// Replaces every reference to recipe `from`, whether it built the building or is the recipe
+    /// it produces, with recipe `to`.
+    pub fn insert_recipe(&mut self, from: &'t str, to: &'t str) {
+        self.recipes.insert(from, to);
+    }
+
+    /// Replaces a building along with the recipe it is built with.
+    pub fn with_building(
+        mut self,
+        (from_type, from_recipe): (&'t str, &'t str),
+        (to_type, to_recipe): (&'t str, &'t str),
+    ) -> Self {
+        self.insert_type(from_type, to_type);
+        self.insert_recipe(from_recipe, to_recipe);
+        self
+    }
+
+    pub fn with_recipe(mut self, from: &'t str, to: &'t str) -> Self {
+        self.insert_recipe(from, to);
+        self
+    }
+
+    pub fn type_path(&self, type_path: &str) -> Option<&'t str> {
+        self.types.get(type_path).copied()
+    }
+
+    pub fn recipe(&self, recipe: &str) -> Option<&'t str> {
+        self.recipes.get(recipe).copied()
+    }
+}
+
+impl BlueprintBody {
+    /// Replaces building types and recipe references using `swap`. Actors named after their old
+    /// class are renamed after the new one, keeping their number when it is free, and every
+    /// reference to them follows. Returns the renamed instance names, mapping the old names to
+    /// the new ones.
+    pub fn swap_recipes(&mut self, swap: &RecipeSwap) -> HashMap<String, String> {
+        let mut names = self.instance_names();
+        let mut renames = HashMap::new();
+
+        for actor in self.actor_headers_mut() {
+            let Some(type_path) = swap.type_path(&actor.type_path) else {
+                continue;
+            };
+
+            let old_prefix = format!("{LEVEL_PATH}{}_", class_name(&actor.type_path));
+            if let Some(number) = actor.instance_name.strip_prefix(&old_prefix) {
+                let renamed = format!("{LEVEL_PATH}{}_{number}", class_name(type_path));
+                let new_name = if names.reserve(&renamed) {
+                    renamed
+                } else {
+                    names.allocate(type_path)
+                };
+                renames.insert(actor.instance_name.clone(), new_name);
+            }
+
+            actor.type_path = type_path.to_owned();
+        }
+
+        self.rename_actors(&renames);
+        self.for_each_path_name_mut(&mut |path_name| {
+            if let Some(recipe) = swap.recipe(path_name) {
+                *path_name = recipe.to_owned();
+            }
+        });
+
+        renames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    const MK3: (&str, &str) = (
+        "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk3/Build_ConveyorBeltMk3.Build_ConveyorBeltMk3_C\0",
+        "/Game/FactoryGame/Recipes/Buildings/Recipe_ConveyorBeltMk3.Recipe_ConveyorBeltMk3_C\0",
+    );
+    const MK5: (&str, &str) = (
+        "/Game/FactoryGame/Buildable/Factory/ConveyorBeltMk5/Build_ConveyorBeltMk5.Build_ConveyorBeltMk5_C\0",
+        "/Game/FactoryGame/Recipes/Buildings/Recipe_ConveyorBeltMk5.Recipe_ConveyorBeltMk5_C\0",
+    );
+
+    #[test]
+    fn check_swap_recipes() {
+        const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let belts = body.query().type_path(MK3.0).iter().count();
+        assert_ne!(belts, 0);
+
+        let renames = body.swap_recipes(&RecipeSwap::new().with_building(MK3, MK5));
+        assert_eq!(renames.len(), belts);
+        for (old, new) in &renames {
+            assert!(old.contains("Build_ConveyorBeltMk3_C_"));
+            assert_eq!(
+                new,
+                &old.replace("Build_ConveyorBeltMk3_C_", "Build_ConveyorBeltMk5_C_")
+            );
+        }
+
+        assert_eq!(body.query().type_path(MK3.0).iter().count(), 0);
+        assert_eq!(body.query().type_path(MK5.0).iter().count(), belts);
+        assert_eq!(
+            body.query()
+                .reference("mBuiltWithRecipe", MK5.1)
+                .iter()
+                .count(),
+            belts
+        );
+        assert_eq!(body.validate_names(), []);
+
+        // Nothing references the old belts or their components anymore
+        body.for_each_path_name_mut(&mut |path_name| {
+            assert!(!path_name.contains("ConveyorBeltMk3"))
+        });
+    }
+}
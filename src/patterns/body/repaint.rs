@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+
+use crate::patterns::body::{
+    BlueprintBody, CUSTOMIZATION_PROPERTY, LinearColor, ObjectHeaderType, ObjectType, glob_match,
+};
+
+/// Colour slot of buildings saved before swatches were introduced
+const COLOR_SLOT: &str = "mColorSlot";
+
+/// Repaints the buildings of a blueprint, see [`BlueprintBody::repaint`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Repaint {
+    swatches: HashMap<u8, u8>,
+    colors: Option<(LinearColor, Option<LinearColor>)>,
+    skin: Option<String>,
+    type_path: Option<String>,
+}
+
+impl Repaint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves buildings painted with swatch slot `from` to slot `to`. Slots are bytes like the
+    /// colour slot of older buildings, so every remap applies to both.
+    pub fn swatch(mut self, from: u8, to: u8) -> Self {
+        self.swatches.insert(from, to);
+        self
+    }
+
+    /// Paints every building with custom colours, which takes precedence over the swatches.
+    pub fn colors(mut self, primary: LinearColor, secondary: Option<LinearColor>) -> Self {
+        self.colors = Some((primary, secondary));
+        self
+    }
+
+    /// Gives every building the skin descriptor `skin`, including the trailing nul.
+    pub fn skin(mut self, skin: &str) -> Self {
+        self.skin = Some(skin.to_owned());
+        self
+    }
+
+    /// Only repaints buildings whose type path matches the glob `pattern`, see [`glob_match`].
+    pub fn type_path(mut self, pattern: &str) -> Self {
+        self.type_path = Some(pattern.to_owned());
+        self
+    }
+}
+
+impl BlueprintBody {
+    /// Repaints the actors matching the type path of `repaint`. Both the customization data and
+    /// the colour slot of older buildings are remapped. Returns the number of actors that
+    /// changed.
+    pub fn repaint(&mut self, repaint: &Repaint) -> Result<usize> {
+        let mut repainted = 0;
+
+        for entity in self.entities_mut() {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                (&*entity.header, entity.object)
+            else {
+                continue;
+            };
+            if repaint
+                .type_path
+                .as_ref()
+                .is_some_and(|pattern| !glob_match(pattern, &header.type_path))
+            {
+                continue;
+            }
+
+            let before = actor.clone();
+
+            if let Some(slot) = actor.get::<u8>(COLOR_SLOT)
+                && let Some(&new_slot) = repaint.swatches.get(&slot)
+            {
+                actor.set(COLOR_SLOT, new_slot)?;
+            }
+
+            if actor.properties.find(CUSTOMIZATION_PROPERTY).is_some() {
+                let mut customization = actor.customization();
+                match repaint.colors {
+                    Some((primary, secondary)) => {
+                        customization.set_custom_colors(primary, secondary)
+                    }
+                    None => {
+                        if let Some(&slot) = customization
+                            .swatch_slot()
+                            .and_then(|slot| u8::try_from(slot).ok())
+                            .and_then(|slot| repaint.swatches.get(&slot))
+                        {
+                            customization.set_swatch_slot(u32::from(slot));
+                        }
+                    }
+                }
+                if let Some(skin) = &repaint.skin {
+                    customization.skin = Some(skin.clone());
+                }
+
+                actor.set_customization(&customization)?;
+            }
+
+            if *actor != before {
+                repainted += 1;
+            }
+        }
+
+        Ok(repainted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    fn slots(body: &BlueprintBody) -> Vec<Option<u32>> {
+        body.objects
+            .iter()
+            .filter_map(|object| match object {
+                ObjectType::Actor(actor) => Some(actor.customization().swatch_slot()),
+                ObjectType::Component(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_repaint_swatches() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let before = slots(&body);
+        let moved = before.iter().filter(|slot| **slot == Some(17)).count();
+        assert_ne!(moved, 0);
+
+        let repainted = body
+            .repaint(&Repaint::new().swatch(17, 3))
+            .expect("Repaint should succeed");
+        assert_eq!(repainted, moved);
+        for (before, after) in before.iter().zip(slots(&body)) {
+            match before {
+                Some(17) => assert_eq!(after, Some(3)),
+                before => assert_eq!(after, *before),
+            }
+        }
+    }
+
+    #[test]
+    fn check_repaint_colors() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let color = LinearColor {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+
+        let repaint = Repaint::new()
+            .colors(color, None)
+            .skin("/Game/Skin.Skin_C\0")
+            .type_path("*Build_GeneratorCoal_C");
+        assert_eq!(body.repaint(&repaint).expect("Repaint should succeed"), 3);
+
+        for entity in body.entities() {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                (entity.header, entity.object)
+            else {
+                continue;
+            };
+            let customization = actor.customization();
+            let is_generator = glob_match("*Build_GeneratorCoal_C", &header.type_path);
+            assert_eq!(customization.primary_color == Some(color), is_generator);
+            assert_eq!(customization.skin.is_some(), is_generator);
+        }
+    }
+
+    #[test]
+    fn check_repaint_color_slot() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let ObjectType::Actor(actor) = &mut body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .insert(COLOR_SLOT, 2u8)
+            .expect("Insert should succeed");
+
+        body.repaint(&Repaint::new().swatch(2, 4))
+            .expect("Repaint should succeed");
+        let ObjectType::Actor(actor) = &body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        assert_eq!(actor.get::<u8>(COLOR_SLOT), Some(4));
+    }
+}
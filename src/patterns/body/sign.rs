@@ -0,0 +1,393 @@
+use color_eyre::Result;
+
+use crate::patterns::body::{
+    ActorObject, ArrayValue, BlueprintBody, LinearColor, ObjectType, PropertyList, PropertyType,
+    StrProperty, StructDataType, Value, empty_struct, update,
+};
+
+/// Property holding the text, colours and icons of a sign
+pub const SIGN_DATA: &str = "mSignData";
+/// Struct type of `mSignData`
+pub const PREFAB_SIGN_DATA: &str = "PrefabSignData\0";
+/// Struct type of the elements of `TextElementData`
+pub const TEXT_ELEMENT: &str = "PrefabTextElementSaveData\0";
+/// Struct type of the elements of `IconElementData`
+pub const ICON_ELEMENT: &str = "PrefabIconElementSaveData\0";
+
+const FOREGROUND_COLOR: &str = "ForegroundColor";
+const BACKGROUND_COLOR: &str = "BackgroundColor";
+const TEXT_ELEMENT_DATA: &str = "TextElementData";
+const ICON_ELEMENT_DATA: &str = "IconElementData";
+const ELEMENT_NAME: &str = "ElementName";
+const TEXT: &str = "Text";
+const FONT_SIZE: &str = "FontSize";
+const ICON_ID: &str = "IconID";
+
+/// Text shown by one element of a sign layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignText {
+    pub element: String,
+    pub text: String,
+    /// Size of the text, `None` for the default of the layout
+    pub font_size: Option<i32>,
+}
+
+/// Icon shown by one element of a sign layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignIcon {
+    pub element: String,
+    /// ID of the icon in the game's icon library
+    pub icon: i32,
+}
+
+/// Text, colours and icons of a sign, read from its `mSignData` struct. Element names and texts
+/// include the trailing nul, missing values are `None`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SignData {
+    pub foreground: Option<LinearColor>,
+    pub background: Option<LinearColor>,
+    pub texts: Vec<SignText>,
+    pub icons: Vec<SignIcon>,
+}
+
+/// A string property holding `value`, as plain strings become object references.
+fn string(value: &str) -> Value {
+    Value::Property(PropertyType::StrProperty(StrProperty {
+        index: 0,
+        value: value.to_owned(),
+    }))
+}
+
+/// Properties of the elements of the struct array at `path`.
+fn elements<'p>(data: &'p PropertyList, path: &str) -> impl Iterator<Item = &'p PropertyList> {
+    let elements = match data.get::<&ArrayValue>(path) {
+        Some(ArrayValue::Struct { elements, .. }) => elements.as_slice(),
+        _ => &[],
+    };
+
+    elements.iter().filter_map(|element| match element {
+        StructDataType::Other { list, .. } => Some(list),
+        _ => None,
+    })
+}
+
+/// Replaces the struct array at `path` with one element per item of `items`, written by
+/// `write` over the old element of the same name so properties not covered are kept.
+fn write_elements<T>(
+    properties: &mut PropertyList,
+    path: &str,
+    struct_name: &str,
+    items: &[T],
+    name: impl Fn(&T) -> &str,
+    write: impl Fn(&T, &mut PropertyList) -> Result<()>,
+) -> Result<()> {
+    let existing = properties.find_path(path).is_some();
+    if items.is_empty() && !existing {
+        return Ok(());
+    }
+
+    let old: Vec<PropertyList> = elements(properties, path).cloned().collect();
+    let mut new = Vec::with_capacity(items.len());
+    for item in items {
+        let mut list = old
+            .iter()
+            .find(|list| list.get::<&str>(ELEMENT_NAME) == Some(name(item)))
+            .cloned()
+            .unwrap_or(PropertyList(Vec::new()));
+        update(&mut list, ELEMENT_NAME, Some(string(name(item))))?;
+        write(item, &mut list)?;
+        new.push(StructDataType::Other {
+            name: struct_name.to_owned(),
+            list,
+        });
+    }
+
+    let name = path.rsplit_once('.').map_or(path, |(_, name)| name);
+    let array = ArrayValue::Struct {
+        name: format!("{name}\0"),
+        struct_name: struct_name.to_owned(),
+        elements: new,
+    };
+    if existing {
+        properties.set(path, array)
+    } else {
+        properties.insert(path, array)
+    }
+}
+
+impl SignData {
+    /// Reads the sign data of an object, `None` if it is not a sign.
+    pub fn read(properties: &PropertyList) -> Option<Self> {
+        let data = properties.get::<&PropertyList>(SIGN_DATA)?;
+        let name = |list: &PropertyList| list.get::<&str>(ELEMENT_NAME).map(ToOwned::to_owned);
+
+        Some(SignData {
+            foreground: data.get(FOREGROUND_COLOR),
+            background: data.get(BACKGROUND_COLOR),
+            texts: elements(data, TEXT_ELEMENT_DATA)
+                .filter_map(|list| {
+                    Some(SignText {
+                        element: name(list)?,
+                        text: list.get::<&str>(TEXT).unwrap_or_default().to_owned(),
+                        font_size: list.get(FONT_SIZE),
+                    })
+                })
+                .collect(),
+            icons: elements(data, ICON_ELEMENT_DATA)
+                .filter_map(|list| {
+                    Some(SignIcon {
+                        element: name(list)?,
+                        icon: list.get(ICON_ID)?,
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    /// Writes the values back into the `mSignData` struct of `properties`, creating it if
+    /// needed. Properties of the struct and its elements this view does not cover are kept.
+    pub fn write(&self, properties: &mut PropertyList) -> Result<()> {
+        if properties.find(SIGN_DATA).is_none() {
+            properties.insert(SIGN_DATA, empty_struct(PREFAB_SIGN_DATA))?;
+        }
+
+        let path = |name| format!("{SIGN_DATA}.{name}");
+        update(properties, &path(FOREGROUND_COLOR), self.foreground)?;
+        update(properties, &path(BACKGROUND_COLOR), self.background)?;
+        write_elements(
+            properties,
+            &path(TEXT_ELEMENT_DATA),
+            TEXT_ELEMENT,
+            &self.texts,
+            |text| &text.element,
+            |text, list| {
+                update(list, TEXT, Some(string(&text.text)))?;
+                update(list, FONT_SIZE, text.font_size)
+            },
+        )?;
+        write_elements(
+            properties,
+            &path(ICON_ELEMENT_DATA),
+            ICON_ELEMENT,
+            &self.icons,
+            |icon| &icon.element,
+            |icon, list| update(list, ICON_ID, Some(icon.icon)),
+        )
+    }
+
+    /// Text of the element named `element`, both including the trailing nul.
+    pub fn text(&self, element: &str) -> Option<&str> {
+        self.texts
+            .iter()
+            .find(|text| text.element == element)
+            .map(|text| text.text.as_str())
+    }
+
+    /// Sets the text of the element named `element`, adding the element if the sign has none.
+    pub fn set_text(&mut self, element: &str, text: &str) {
+        match self.texts.iter_mut().find(|item| item.element == element) {
+            Some(item) => item.text = text.to_owned(),
+            None => self.texts.push(SignText {
+                element: element.to_owned(),
+                text: text.to_owned(),
+                font_size: None,
+            }),
+        }
+    }
+
+    /// Replaces every occurrence of `from` with `to` in the texts, returning the number of
+    /// texts changed.
+    pub fn replace_text(&mut self, from: &str, to: &str) -> usize {
+        if from.is_empty() {
+            return 0;
+        }
+
+        let mut changed = 0;
+        for text in &mut self.texts {
+            if text.text.contains(from) {
+                text.text = text.text.replace(from, to);
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
+
+impl ActorObject {
+    pub fn sign(&self) -> Option<SignData> {
+        SignData::read(&self.properties)
+    }
+
+    /// See [`SignData::write`].
+    pub fn set_sign(&mut self, sign: &SignData) -> Result<()> {
+        sign.write(&mut self.properties)
+    }
+}
+
+impl BlueprintBody {
+    /// Replaces `from` with `to` in the text of every sign, such as the name of a module
+    /// repeated across its signs. Returns the number of signs changed.
+    pub fn replace_sign_text(&mut self, from: &str, to: &str) -> Result<usize> {
+        let mut changed = 0;
+
+        for object in &mut self.objects {
+            let ObjectType::Actor(actor) = object else {
+                continue;
+            };
+            let Some(mut sign) = actor.sign() else {
+                continue;
+            };
+            if sign.replace_text(from, to) > 0 {
+                actor.set_sign(&sign)?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{
+            Blueprint,
+            body::{ActorHeader, ObjectHeaderType, blueprint_body},
+        },
+        transform::{Transform, Vec3},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+    const SIGN: &str = "/Game/FactoryGame/Buildable/Factory/SignDigital/Build_StandaloneWidgetSign_Medium.Build_StandaloneWidgetSign_Medium_C\0";
+
+    fn add_sign(body: &mut BlueprintBody, sign: &SignData) -> String {
+        let instance_name = body.instance_names().allocate(SIGN);
+        let mut actor = ActorObject::buildable(PropertyList(Vec::new()));
+        actor.set_sign(sign).expect("Sign should be written");
+        let header = ActorHeader::new(
+            SIGN,
+            instance_name.clone(),
+            &Transform::from_translation(Vec3::ZERO),
+        );
+        body.insert(ObjectHeaderType::Actor(header), ObjectType::Actor(actor))
+            .expect("Sign should be inserted");
+
+        instance_name
+    }
+
+    fn sign(body: &BlueprintBody, instance_name: &str) -> SignData {
+        let ObjectType::Actor(actor) = body
+            .entity(instance_name)
+            .expect("Sign should exist")
+            .object
+        else {
+            panic!("Expected an actor object");
+        };
+        actor.sign().expect("Actor should be a sign")
+    }
+
+    #[test]
+    fn check_sign_data() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let ObjectType::Actor(generator) = &body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        assert_eq!(generator.sign(), None);
+
+        let mut data = SignData {
+            foreground: Some(LinearColor::from_hex("#FFFFFF").expect("Hex should parse")),
+            background: Some(LinearColor::from_hex("#FA9549").expect("Hex should parse")),
+            texts: vec![SignText {
+                element: "Name\0".to_owned(),
+                text: "Coal Power\0".to_owned(),
+                font_size: Some(48),
+            }],
+            icons: vec![SignIcon {
+                element: "Icon\0".to_owned(),
+                icon: 271,
+            }],
+        };
+        data.set_text("Subtitle\0", "Module 1\0");
+        let name = add_sign(&mut body, &data);
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+
+        let read = sign(&parsed, &name);
+        assert_eq!(read, data);
+        assert_eq!(read.text("Name\0"), Some("Coal Power\0"));
+        assert_eq!(read.texts[1].font_size, None);
+        assert_eq!(
+            read.background.map(|color| color.to_hex()).as_deref(),
+            Some("#FA9549")
+        );
+
+        // Writing back unchanged data leaves the properties as they were
+        let ObjectType::Actor(actor) = body.entity(&name).expect("Sign should exist").object else {
+            panic!("Expected an actor object");
+        };
+        let mut edited = actor.clone();
+        edited.set_sign(&read).expect("Write should succeed");
+        assert_eq!(&edited, actor);
+    }
+
+    #[test]
+    fn check_replace_sign_text() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let mut data = SignData::default();
+        data.set_text("Name\0", "Module 1 Coal\0");
+        data.set_text("Subtitle\0", "Module 1\0");
+        let first = add_sign(&mut body, &data);
+        data.set_text("Name\0", "Steel\0");
+        data.texts.pop();
+        let second = add_sign(&mut body, &data);
+
+        assert_eq!(
+            body.replace_sign_text("Module 1", "Module 2")
+                .expect("Replace should succeed"),
+            1
+        );
+        let first = sign(&body, &first);
+        assert_eq!(first.text("Name\0"), Some("Module 2 Coal\0"));
+        assert_eq!(first.text("Subtitle\0"), Some("Module 2\0"));
+        assert_eq!(sign(&body, &second).text("Name\0"), Some("Steel\0"));
+
+        assert_eq!(
+            body.replace_sign_text("Missing", "Text")
+                .expect("Replace should succeed"),
+            0
+        );
+    }
+
+    #[test]
+    fn check_non_ascii_sign_text() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let mut data = SignData::default();
+        data.set_text("Name\0", "Kühlwasser 20°C\0");
+        let name = add_sign(&mut body, &data);
+
+        assert_eq!(
+            body.replace_sign_text("20°C", "25°C →")
+                .expect("Replace should succeed"),
+            1
+        );
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+        assert_eq!(
+            sign(&parsed, &name).text("Name\0"),
+            Some("Kühlwasser 25°C →\0")
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use color_eyre::{Result, eyre::bail};
+
+use crate::{
+    patterns::body::{
+        ActorObject, ArrayValue, Entity, EntityMut, ObjectHeaderType, ObjectType, PropertyList,
+        StructDataType, empty_struct, update,
+    },
+    transform::{Quat, Transform, Vec3},
+};
+
+/// Property holding the points of a belt, pipe or railway spline
+pub const SPLINE_DATA: &str = "mSplineData";
+/// Struct type of the spline points
+pub const SPLINE_POINT_DATA: &str = "SplinePointData\0";
+/// Length of the spline cached by some buildings, such as pipes
+pub const CACHED_LENGTH: &str = "mCachedLength";
+/// Top of a conveyor lift relative to the actor
+pub const TOP_TRANSFORM: &str = "mTopTransform";
+/// Struct type of `mTopTransform`
+pub const TRANSFORM: &str = "Transform\0";
+
+const LOCATION: &str = "Location";
+const ARRIVE_TANGENT: &str = "ArriveTangent";
+const LEAVE_TANGENT: &str = "LeaveTangent";
+const ROTATION: &str = "Rotation";
+const TRANSLATION: &str = "Translation";
+const SCALE: &str = "Scale3D";
+
+/// Samples per segment when measuring its length
+const LENGTH_SAMPLES: usize = 64;
+
+/// A point of a spline, relative to the actor it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplinePoint {
+    pub location: Vec3,
+    pub arrive_tangent: Vec3,
+    pub leave_tangent: Vec3,
+}
+
+impl SplinePoint {
+    /// A point whose curve passes straight through it along `tangent`.
+    pub fn new(location: Vec3, tangent: Vec3) -> Self {
+        SplinePoint {
+            location,
+            arrive_tangent: tangent,
+            leave_tangent: tangent,
+        }
+    }
+
+    fn read(data: &StructDataType) -> Option<Self> {
+        let StructDataType::Other { list, .. } = data else {
+            return None;
+        };
+
+        Some(SplinePoint {
+            location: list.get(LOCATION)?,
+            arrive_tangent: list.get(ARRIVE_TANGENT)?,
+            leave_tangent: list.get(LEAVE_TANGENT)?,
+        })
+    }
+
+    fn to_struct(self) -> Result<StructDataType> {
+        let mut list = PropertyList(Vec::new());
+        list.insert(LOCATION, self.location)?;
+        list.insert(ARRIVE_TANGENT, self.arrive_tangent)?;
+        list.insert(LEAVE_TANGENT, self.leave_tangent)?;
+
+        Ok(StructDataType::Other {
+            name: SPLINE_POINT_DATA.to_owned(),
+            list,
+        })
+    }
+}
+
+/// Shape of a belt, pipe or railway, read from its `mSplineData` array. Each segment is a cubic
+/// Hermite curve between two points, leaving the first along its leave tangent and arriving at
+/// the second along its arrive tangent. Conveyor lifts have no spline, see [`Lift`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Spline {
+    pub points: Vec<SplinePoint>,
+}
+
+impl Spline {
+    /// Reads the spline of an object, `None` if it has none or it is malformed.
+    pub fn read(properties: &PropertyList) -> Option<Self> {
+        let ArrayValue::Struct { elements, .. } = properties.get::<&ArrayValue>(SPLINE_DATA)?
+        else {
+            return None;
+        };
+
+        let points = elements
+            .iter()
+            .map(SplinePoint::read)
+            .collect::<Option<_>>()?;
+        Some(Spline { points })
+    }
+
+    /// Writes the points into the `mSplineData` array of `properties`, creating it if needed.
+    /// A cached length is updated to match.
+    pub fn write(&self, properties: &mut PropertyList) -> Result<()> {
+        let elements = self
+            .points
+            .iter()
+            .map(|point| point.to_struct())
+            .collect::<Result<_>>()?;
+        let array = ArrayValue::Struct {
+            name: format!("{SPLINE_DATA}\0"),
+            struct_name: SPLINE_POINT_DATA.to_owned(),
+            elements,
+        };
+
+        if properties.find(SPLINE_DATA).is_some() {
+            properties.set(SPLINE_DATA, array)?;
+        } else {
+            properties.insert(SPLINE_DATA, array)?;
+        }
+        if properties.find(CACHED_LENGTH).is_some() {
+            properties.set(CACHED_LENGTH, self.length() as f32)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn segments(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Points at the start and end of `segment`.
+    fn segment(&self, segment: usize) -> Option<(SplinePoint, SplinePoint)> {
+        Some((*self.points.get(segment)?, *self.points.get(segment + 1)?))
+    }
+
+    /// Location along `segment` at `t` between 0 and 1, `None` if there is no such segment.
+    pub fn location(&self, segment: usize, t: f64) -> Option<Vec3> {
+        let (start, end) = self.segment(segment)?;
+        let (t2, t3) = (t * t, t * t * t);
+
+        Some(
+            start.location * (2.0 * t3 - 3.0 * t2 + 1.0)
+                + start.leave_tangent * (t3 - 2.0 * t2 + t)
+                + end.location * (-2.0 * t3 + 3.0 * t2)
+                + end.arrive_tangent * (t3 - t2),
+        )
+    }
+
+    /// Derivative of [`Spline::location`], the tangent of the curve at `t`.
+    pub fn tangent(&self, segment: usize, t: f64) -> Option<Vec3> {
+        let (start, end) = self.segment(segment)?;
+        let t2 = t * t;
+
+        Some(
+            start.location * (6.0 * t2 - 6.0 * t)
+                + start.leave_tangent * (3.0 * t2 - 4.0 * t + 1.0)
+                + end.location * (-6.0 * t2 + 6.0 * t)
+                + end.arrive_tangent * (3.0 * t2 - 2.0 * t),
+        )
+    }
+
+    /// Length of `segment` in centimetres, `None` if there is no such segment.
+    pub fn segment_length(&self, segment: usize) -> Option<f64> {
+        let locations = (0..=LENGTH_SAMPLES)
+            .map(|sample| self.location(segment, sample as f64 / LENGTH_SAMPLES as f64))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(
+            locations
+                .windows(2)
+                .map(|pair| pair[1].distance(pair[0]))
+                .sum(),
+        )
+    }
+
+    /// Length of the whole spline in centimetres.
+    pub fn length(&self) -> f64 {
+        (0..self.segments())
+            .filter_map(|segment| self.segment_length(segment))
+            .sum()
+    }
+
+    /// Inserts `point` before the point at `index`, or at the end for the number of points.
+    pub fn insert(&mut self, index: usize, point: SplinePoint) -> Result<()> {
+        if index > self.points.len() {
+            bail!(
+                "Spline point {index} is out of bounds for {} points",
+                self.points.len()
+            );
+        }
+
+        self.points.insert(index, point);
+        Ok(())
+    }
+
+    /// Splits `segment` at `t` between 0 and 1 by inserting a point on the curve, keeping the
+    /// shape of the spline. Returns the index of the new point.
+    pub fn split(&mut self, segment: usize, t: f64) -> Result<usize> {
+        let (Some(location), Some(tangent)) = (self.location(segment, t), self.tangent(segment, t))
+        else {
+            bail!(
+                "Spline segment {segment} is out of bounds for {} segments",
+                self.segments()
+            );
+        };
+
+        // Both halves are reparameterised from 0 to 1, which scales their tangents
+        self.points[segment].leave_tangent = self.points[segment].leave_tangent * t;
+        self.points[segment + 1].arrive_tangent =
+            self.points[segment + 1].arrive_tangent * (1.0 - t);
+        self.points.insert(
+            segment + 1,
+            SplinePoint {
+                location,
+                arrive_tangent: tangent * t,
+                leave_tangent: tangent * (1.0 - t),
+            },
+        );
+
+        Ok(segment + 1)
+    }
+
+    /// Recomputes every tangent from the locations so the curve flows smoothly through them.
+    /// Inner points point from their previous to their next neighbour, ends point at their only
+    /// neighbour.
+    pub fn retangent(&mut self) {
+        let locations: Vec<Vec3> = self.points.iter().map(|point| point.location).collect();
+        let last = locations.len().saturating_sub(1);
+
+        for (index, point) in self.points.iter_mut().enumerate() {
+            let tangent = match index {
+                _ if last == 0 => Vec3::ZERO,
+                0 => locations[1] - locations[0],
+                index if index == last => locations[last] - locations[last - 1],
+                index => (locations[index + 1] - locations[index - 1]) * 0.5,
+            };
+            point.arrive_tangent = tangent;
+            point.leave_tangent = tangent;
+        }
+    }
+
+    /// The spline with `transform` applied, such as an actor transform to move the points into
+    /// world space.
+    pub fn transform(&self, transform: &Transform) -> Spline {
+        let points = self
+            .points
+            .iter()
+            .map(|point| SplinePoint {
+                location: transform.transform_point(point.location),
+                arrive_tangent: transform.transform_vector(point.arrive_tangent),
+                leave_tangent: transform.transform_vector(point.leave_tangent),
+            })
+            .collect();
+
+        Spline { points }
+    }
+}
+
+/// Shape of a conveyor lift, which runs straight from its actor at the bottom to the top stored
+/// in `mTopTransform`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lift {
+    /// Bottom of the lift in blueprint space, the transform of the actor
+    pub bottom: Transform,
+    /// Top of the lift relative to the bottom
+    pub top: Transform,
+}
+
+impl Lift {
+    /// Top of the lift in blueprint space.
+    pub fn world_top(&self) -> Transform {
+        self.bottom * self.top
+    }
+
+    /// Height of the top above the bottom in centimetres.
+    pub fn height(&self) -> f64 {
+        self.top.translation.z
+    }
+}
+
+impl ActorObject {
+    /// Top of a conveyor lift relative to the actor, `None` if it is not a lift. Rotation and
+    /// scale left out of `mTopTransform` take their defaults.
+    pub fn lift_top(&self) -> Option<Transform> {
+        let path = |name| format!("{TOP_TRANSFORM}.{name}");
+
+        Some(Transform::new(
+            self.get(&path(ROTATION)).unwrap_or(Quat::IDENTITY),
+            self.get(&path(TRANSLATION))?,
+            self.get(&path(SCALE)).unwrap_or(Vec3::ONE),
+        ))
+    }
+
+    /// Writes `top` into `mTopTransform`, creating it if needed.
+    pub fn set_lift_top(&mut self, top: &Transform) -> Result<()> {
+        if self.properties.find(TOP_TRANSFORM).is_none() {
+            self.properties
+                .insert(TOP_TRANSFORM, empty_struct(TRANSFORM))?;
+        }
+
+        let path = |name| format!("{TOP_TRANSFORM}.{name}");
+        update(&mut self.properties, &path(ROTATION), Some(top.rotation))?;
+        update(
+            &mut self.properties,
+            &path(TRANSLATION),
+            Some(top.translation),
+        )?;
+        // The game leaves out the default scale
+        let scale = (top.scale != Vec3::ONE).then_some(top.scale);
+        update(&mut self.properties, &path(SCALE), scale)
+    }
+
+    /// Spline relative to the actor, see [`Spline::read`].
+    pub fn spline(&self) -> Option<Spline> {
+        Spline::read(&self.properties)
+    }
+
+    pub fn set_spline(&mut self, spline: &Spline) -> Result<()> {
+        spline.write(&mut self.properties)
+    }
+}
+
+impl Entity<'_> {
+    /// Spline of an actor in world space, following the actor transform.
+    pub fn world_spline(&self) -> Option<Spline> {
+        let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+            (self.header, self.object)
+        else {
+            return None;
+        };
+
+        Some(actor.spline()?.transform(&header.transform()))
+    }
+
+    /// Shape of a conveyor lift, `None` if the entity is not one.
+    pub fn lift(&self) -> Option<Lift> {
+        let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+            (self.header, self.object)
+        else {
+            return None;
+        };
+
+        Some(Lift {
+            bottom: header.transform(),
+            top: actor.lift_top()?,
+        })
+    }
+}
+
+impl EntityMut<'_> {
+    pub fn world_spline(&self) -> Option<Spline> {
+        Entity {
+            header: self.header,
+            object: self.object,
+        }
+        .world_spline()
+    }
+
+    pub fn lift(&self) -> Option<Lift> {
+        Entity {
+            header: self.header,
+            object: self.object,
+        }
+        .lift()
+    }
+
+    /// Moves the top of a conveyor lift to `height` centimetres above its bottom.
+    pub fn set_lift_height(&mut self, height: f64) -> Result<()> {
+        let ObjectType::Actor(actor) = &mut *self.object else {
+            bail!("Only actors can be lifts");
+        };
+        let Some(mut top) = actor.lift_top() else {
+            bail!("Actor is not a conveyor lift");
+        };
+
+        top.translation.z = height;
+        actor.set_lift_top(&top)
+    }
+
+    /// Replaces the spline of an actor with a spline in world space, moving it into the actor
+    /// space.
+    pub fn set_world_spline(&mut self, spline: &Spline) -> Result<()> {
+        let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+            (&*self.header, &mut *self.object)
+        else {
+            bail!("Only actors have splines");
+        };
+
+        actor.set_spline(&spline.transform(&header.transform().inverse()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{Blueprint, body::blueprint_body},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+
+    fn assert_close(a: &Spline, b: &Spline) {
+        assert_eq!(a.points.len(), b.points.len());
+        for (a, b) in a.points.iter().zip(&b.points) {
+            assert!(a.location.abs_diff_eq(b.location, 0.01), "{a:?} != {b:?}");
+            assert!(a.arrive_tangent.abs_diff_eq(b.arrive_tangent, 0.01));
+            assert!(a.leave_tangent.abs_diff_eq(b.leave_tangent, 0.01));
+        }
+    }
+
+    fn belt_spline(blueprint: &Blueprint) -> (String, Spline) {
+        blueprint
+            .body
+            .query()
+            .type_path("*Build_ConveyorBeltMk3_C")
+            .iter()
+            .find_map(|entity| {
+                let spline = entity.world_spline()?;
+                (spline.length() > 700.0).then(|| (entity.instance_name().to_owned(), spline))
+            })
+            .expect("Blueprint should have a belt")
+    }
+
+    #[test]
+    fn check_spline_round_trip() {
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        for entity in blueprint.body.query().has_property(SPLINE_DATA).iter() {
+            let ObjectType::Actor(actor) = entity.object else {
+                panic!("Expected an actor object");
+            };
+            let spline = actor.spline().expect("Spline should be read");
+            assert_eq!(spline.points.len(), 2);
+
+            let mut written = actor.clone();
+            written.set_spline(&spline).expect("Write should succeed");
+            assert_eq!(&written, actor);
+        }
+    }
+
+    #[test]
+    fn check_spline_editing() {
+        let blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let (_, spline) = belt_spline(&blueprint);
+        let length = spline.length();
+        assert!((length - 800.0).abs() < 0.01);
+
+        // Splitting keeps the shape and the length
+        let mut split = spline.clone();
+        let index = split.split(0, 0.25).expect("Split should succeed");
+        assert_eq!(index, 1);
+        assert_eq!(split.points.len(), 3);
+        assert!((split.length() - length).abs() < 0.1);
+        assert!(split.points[1].location.abs_diff_eq(
+            spline.location(0, 0.25).expect("Segment should exist"),
+            0.001
+        ));
+        assert!(split.split(2, 0.5).is_err());
+        assert_eq!(split.location(2, 0.5), None);
+        assert_eq!(split.tangent(2, 0.5), None);
+        assert_eq!(split.segment_length(2), None);
+        assert_eq!(Spline::default().location(0, 0.0), None);
+
+        let mut straight = Spline::default();
+        for x in [0.0, 100.0, 300.0] {
+            let index = straight.points.len();
+            straight
+                .insert(index, SplinePoint::new(Vec3::new(x, 0.0, 0.0), Vec3::ZERO))
+                .expect("Insert should succeed");
+        }
+        assert!(straight.insert(9, straight.points[0]).is_err());
+        straight.retangent();
+        assert_eq!(straight.points[1].leave_tangent, Vec3::new(150.0, 0.0, 0.0));
+        assert!((straight.length() - 300.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn check_world_spline() {
+        let mut blueprint = Blueprint::new(DATA).expect("Parse should succeed");
+        let (name, before) = belt_spline(&blueprint);
+
+        // Moving the actors moves their splines with them
+        let transform = Transform::new(
+            Quat::from_yaw(90.0),
+            Vec3::new(800.0, -400.0, 100.0),
+            Vec3::ONE,
+        );
+        blueprint.body.transform(&transform);
+        let after = blueprint
+            .body
+            .entity(&name)
+            .and_then(|entity| entity.world_spline())
+            .expect("Belt should have a spline");
+        assert_close(&after, &before.transform(&transform));
+
+        // Setting the world spline keeps the actor where it is
+        let mut entity = blueprint.body.entity_mut(&name).expect("Belt should exist");
+        let header = entity.header.clone();
+        let mut raised = after.clone();
+        raised.points[1].location += Vec3::Z * 200.0;
+        entity
+            .set_world_spline(&raised)
+            .expect("Write should succeed");
+        assert_eq!(*entity.header, header);
+        assert_close(
+            &entity.world_spline().expect("Belt should have a spline"),
+            &raised,
+        );
+    }
+
+    #[test]
+    fn check_lift() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let lifts: Vec<(String, Lift)> = body
+            .query()
+            .type_path("*Build_ConveyorLiftMk1_C")
+            .iter()
+            .map(|entity| {
+                let lift = entity.lift().expect("Conveyor lift should have a top");
+                (entity.instance_name().to_owned(), lift)
+            })
+            .collect();
+        assert_eq!(lifts.len(), 3);
+        assert!(
+            body.query()
+                .has_property(SPLINE_DATA)
+                .iter()
+                .all(|entity| entity.lift().is_none())
+        );
+
+        let (name, lift) = &lifts[0];
+        assert!((lift.height() - 400.0).abs() < 0.01);
+        assert!(
+            lift.bottom
+                .translation
+                .abs_diff_eq(Vec3::new(-800.0, 1200.0, 100.0), 0.01)
+        );
+        assert!(
+            lift.world_top()
+                .translation
+                .abs_diff_eq(Vec3::new(-800.0, 1200.0, 500.0), 0.01)
+        );
+
+        // Writing the top back unchanged keeps the properties as they were
+        let mut entity = body.entity_mut(name).expect("Lift should exist");
+        let ObjectType::Actor(actor) = &mut *entity.object else {
+            panic!("Expected an actor object");
+        };
+        let before = actor.clone();
+        actor.set_lift_top(&lift.top).expect("Write should succeed");
+        assert_eq!(*actor, before);
+
+        entity.set_lift_height(800.0).expect("Height should be set");
+        let raised = entity.lift().expect("Actor should be a lift");
+        assert!((raised.height() - 800.0).abs() < 1e-9);
+        assert!(raised.top.rotation.abs_diff_eq(lift.top.rotation, 1e-9));
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+    }
+}
@@ -0,0 +1,359 @@
+use std::fmt;
+
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+
+use crate::{
+    catalog::Catalog,
+    patterns::body::{
+        ActorObject, ArrayValue, BlueprintBody, ObjectHeaderType, ObjectType, PropertyList,
+        StructDataType, class_name, update,
+    },
+};
+
+/// Sort rules of a smart or programmable splitter
+pub const SORT_RULES: &str = "mSortRules";
+/// Struct type of the elements of `mSortRules`
+pub const SPLITTER_SORT_RULE: &str = "SplitterSortRule\0";
+/// Folder of the descriptors that filter groups of items rather than a single item
+pub const FILTERING_RULES_PATH: &str = "/Game/FactoryGame/Resource/FilteringRules";
+/// Number of outputs of a splitter
+pub const SPLITTER_OUTPUTS: u32 = 3;
+
+const ITEM_CLASS: &str = "ItemClass";
+const OUTPUT_INDEX: &str = "OutputIndex";
+
+const SMART_SPLITTER: &str = "Build_ConveyorAttachmentSplitterSmart_C";
+const PROGRAMMABLE_SPLITTER: &str = "Build_ConveyorAttachmentSplitterProgrammable_C";
+
+/// Filters matching every item, no item, items without a rule of their own and items the other
+/// outputs cannot take.
+const FILTERS: [&str; 4] = [
+    "Desc_Wildcard_C",
+    "Desc_None_C",
+    "Desc_AnyUndefined_C",
+    "Desc_Overflow_C",
+];
+
+/// Reference to a filter descriptor such as `Desc_Overflow`.
+pub fn filter_reference(filter: &str) -> String {
+    format!("{FILTERING_RULES_PATH}/{filter}.{filter}_C\0")
+}
+
+/// Whether buildings of type `type_path` sort items by rules.
+pub fn is_sorting_splitter(type_path: &str) -> bool {
+    matches!(
+        class_name(type_path),
+        SMART_SPLITTER | PROGRAMMABLE_SPLITTER
+    )
+}
+
+/// Sends items matching a descriptor to an output of a splitter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortRule {
+    /// Reference to an item descriptor or a filter, including the trailing nul
+    pub item: String,
+    /// Index of the output, below [`SPLITTER_OUTPUTS`]
+    pub output: u32,
+}
+
+impl SortRule {
+    fn read(list: &PropertyList) -> Option<Self> {
+        Some(SortRule {
+            item: list.get::<&str>(ITEM_CLASS)?.to_owned(),
+            output: u32::try_from(list.get::<i32>(OUTPUT_INDEX)?).ok()?,
+        })
+    }
+
+    fn to_struct(&self) -> Result<StructDataType> {
+        let mut list = PropertyList(Vec::new());
+        list.insert(ITEM_CLASS, self.item.as_str())?;
+        list.insert(OUTPUT_INDEX, i32::try_from(self.output)?)?;
+
+        Ok(StructDataType::Other {
+            name: SPLITTER_SORT_RULE.to_owned(),
+            list,
+        })
+    }
+}
+
+/// Problem with the sort rules of a splitter, see [`BlueprintBody::validate_sort_rules`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortRuleError {
+    /// A rule filters an item that is not in the catalog
+    UnknownItem { splitter: String, item: String },
+    /// A rule sends items to an output the splitter does not have
+    InvalidOutput { splitter: String, output: u32 },
+    /// A smart splitter has more than one rule for an output
+    TooManyRules { splitter: String, output: u32 },
+}
+
+impl fmt::Display for SortRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortRuleError::UnknownItem { splitter, item } => write!(
+                f,
+                "splitter {splitter:?} sorts {item:?} which is not an item in the catalog"
+            ),
+            SortRuleError::InvalidOutput { splitter, output } => {
+                write!(f, "splitter {splitter:?} has no output {output}")
+            }
+            SortRuleError::TooManyRules { splitter, output } => write!(
+                f,
+                "smart splitter {splitter:?} has more than one rule for output {output}"
+            ),
+        }
+    }
+}
+
+/// Rules of the splitter of type `type_path` that it cannot hold, without looking up items.
+fn rule_errors(splitter: &str, type_path: &str, rules: &[SortRule]) -> Vec<SortRuleError> {
+    let mut errors = Vec::new();
+
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.output >= SPLITTER_OUTPUTS {
+            errors.push(SortRuleError::InvalidOutput {
+                splitter: splitter.to_owned(),
+                output: rule.output,
+            });
+        } else if class_name(type_path) == SMART_SPLITTER
+            && rules[..index]
+                .iter()
+                .filter(|other| other.output == rule.output)
+                .count()
+                == 1
+        {
+            errors.push(SortRuleError::TooManyRules {
+                splitter: splitter.to_owned(),
+                output: rule.output,
+            });
+        }
+    }
+
+    errors
+}
+
+impl ActorObject {
+    /// Rules of a smart or programmable splitter, in the order they are stored.
+    pub fn sort_rules(&self) -> Vec<SortRule> {
+        match self.get::<&ArrayValue>(SORT_RULES) {
+            Some(ArrayValue::Struct { elements, .. }) => elements
+                .iter()
+                .filter_map(|element| match element {
+                    StructDataType::Other { list, .. } => SortRule::read(list),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the sort rules, removing `mSortRules` when there are none.
+    pub fn set_sort_rules(&mut self, rules: &[SortRule]) -> Result<()> {
+        if rules.is_empty() {
+            self.remove(SORT_RULES);
+            return Ok(());
+        }
+
+        let array = ArrayValue::Struct {
+            name: format!("{SORT_RULES}\0"),
+            struct_name: SPLITTER_SORT_RULE.to_owned(),
+            elements: rules
+                .iter()
+                .map(SortRule::to_struct)
+                .collect::<Result<_>>()?,
+        };
+        update(&mut self.properties, SORT_RULES, Some(array))
+    }
+}
+
+impl BlueprintBody {
+    /// Replaces the sort rules of the splitter `splitter`. Fails if it is not a smart or
+    /// programmable splitter or cannot hold the rules, items are checked by
+    /// [`BlueprintBody::validate_sort_rules`].
+    pub fn set_sort_rules(&mut self, splitter: &str, rules: &[SortRule]) -> Result<()> {
+        let entity = self
+            .entity_mut(splitter)
+            .ok_or_else(|| eyre!("{splitter:?} is not in the body"))?;
+        let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+            (&*entity.header, entity.object)
+        else {
+            bail!("{splitter:?} is not a splitter");
+        };
+        if !is_sorting_splitter(&header.type_path) {
+            bail!("{splitter:?} is not a smart or programmable splitter");
+        }
+        if let Some(error) = rule_errors(splitter, &header.type_path, rules).first() {
+            bail!("{error}");
+        }
+
+        actor.set_sort_rules(rules)
+    }
+
+    /// Checks the rules of every smart and programmable splitter: their items must be filters
+    /// or items of `catalog`, their outputs must exist and smart splitters take one rule per
+    /// output.
+    pub fn validate_sort_rules(&self, catalog: &Catalog) -> Vec<SortRuleError> {
+        let mut errors = Vec::new();
+
+        for entity in self.entities() {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) =
+                (entity.header, entity.object)
+            else {
+                continue;
+            };
+            if !is_sorting_splitter(&header.type_path) {
+                continue;
+            }
+
+            let rules = actor.sort_rules();
+            for rule in &rules {
+                if !FILTERS.contains(&class_name(&rule.item)) && catalog.item(&rule.item).is_none()
+                {
+                    errors.push(SortRuleError::UnknownItem {
+                        splitter: header.instance_name.clone(),
+                        item: rule.item.clone(),
+                    });
+                }
+            }
+            errors.extend(rule_errors(
+                &header.instance_name,
+                &header.type_path,
+                &rules,
+            ));
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bp_write::BPWrite,
+        patterns::{Blueprint, body::blueprint_body},
+    };
+    use winnow::Parser;
+
+    const DATA: &[u8] = include_bytes!("../../../Coal Left to Right.sbp");
+    const DOCS: &str = include_str!("../../../Docs Sample.json");
+    const SMART: &str = "/Game/FactoryGame/Buildable/Factory/CA_SplitterSmart/Build_ConveyorAttachmentSplitterSmart.Build_ConveyorAttachmentSplitterSmart_C\0";
+    const PROGRAMMABLE: &str = "/Game/FactoryGame/Buildable/Factory/CA_SplitterProgrammable/Build_ConveyorAttachmentSplitterProgrammable.Build_ConveyorAttachmentSplitterProgrammable_C\0";
+    const CABLE: &str = "/Game/FactoryGame/Resource/Parts/Cable/Desc_Cable.Desc_Cable_C\0";
+    const ROTOR: &str = "/Game/FactoryGame/Resource/Parts/Rotor/Desc_Rotor.Desc_Rotor_C\0";
+    const ORE: &str =
+        "/Game/FactoryGame/Resource/RawResources/OreIron/Desc_OreIron.Desc_OreIron_C\0";
+
+    fn rule(item: &str, output: u32) -> SortRule {
+        SortRule {
+            item: item.to_owned(),
+            output,
+        }
+    }
+
+    /// The coal blueprint with its splitters turned into a smart and a programmable splitter.
+    fn sorting() -> (BlueprintBody, [String; 3]) {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let splitters: Vec<_> = body
+            .query()
+            .type_path("*Build_ConveyorAttachmentSplitter_C")
+            .iter()
+            .map(|entity| entity.instance_name().to_owned())
+            .collect();
+        for (splitter, type_path) in splitters.iter().zip([SMART, PROGRAMMABLE]) {
+            let ObjectHeaderType::Actor(header) = body
+                .entity_mut(splitter)
+                .expect("Splitter should exist")
+                .header
+            else {
+                panic!("Expected an actor header");
+            };
+            header.type_path = type_path.to_owned();
+        }
+
+        let splitters = splitters.try_into().expect("Blueprint has three splitters");
+        (body, splitters)
+    }
+
+    #[test]
+    fn check_sort_rules() {
+        let (mut body, [smart, programmable, plain]) = sorting();
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+
+        let rules = [
+            rule(CABLE, 0),
+            rule(ROTOR, 2),
+            rule(&filter_reference("Desc_Overflow"), 1),
+        ];
+        body.set_sort_rules(&smart, &rules)
+            .expect("Rules should be set");
+        body.set_sort_rules(&programmable, &[rule(CABLE, 0), rule(ROTOR, 0)])
+            .expect("Programmable splitters take several rules per output");
+        assert!(body.set_sort_rules(&plain, &rules).is_err());
+        assert!(
+            body.set_sort_rules(&smart, &[rule(CABLE, 0), rule(ROTOR, 0)])
+                .is_err()
+        );
+        assert!(body.set_sort_rules(&smart, &[rule(CABLE, 3)]).is_err());
+        assert_eq!(body.validate_sort_rules(&catalog), []);
+
+        let mut buf = Vec::new();
+        body.bp_write(&mut buf).expect("Write should succeed");
+        let parsed = blueprint_body
+            .parse(buf.as_slice().into())
+            .expect("Parse should succeed");
+        assert_eq!(parsed, body);
+
+        let ObjectType::Actor(actor) = parsed.entity(&smart).expect("Splitter should exist").object
+        else {
+            panic!("Expected an actor object");
+        };
+        assert_eq!(actor.sort_rules(), rules);
+
+        body.set_sort_rules(&smart, &[])
+            .expect("Rules should be cleared");
+        let ObjectType::Actor(actor) = body.entity(&smart).expect("Splitter should exist").object
+        else {
+            panic!("Expected an actor object");
+        };
+        assert_eq!(actor.properties.find(SORT_RULES), None);
+    }
+
+    #[test]
+    fn check_invalid_sort_rules() {
+        let (mut body, [smart, ..]) = sorting();
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+
+        let ObjectType::Actor(actor) = body
+            .entity_mut(&smart)
+            .expect("Splitter should exist")
+            .object
+        else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .set_sort_rules(&[rule(ORE, 0), rule(CABLE, 0), rule(ROTOR, 5)])
+            .expect("Rules should be written");
+
+        assert_eq!(
+            body.validate_sort_rules(&catalog),
+            [
+                SortRuleError::UnknownItem {
+                    splitter: smart.clone(),
+                    item: ORE.to_owned(),
+                },
+                SortRuleError::TooManyRules {
+                    splitter: smart.clone(),
+                    output: 0,
+                },
+                SortRuleError::InvalidOutput {
+                    splitter: smart,
+                    output: 5,
+                },
+            ]
+        );
+    }
+}
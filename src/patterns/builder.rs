@@ -0,0 +1,274 @@
+use crate::{
+    catalog::Catalog,
+    patterns::{
+        Blueprint,
+        body::{
+            ActorHeader, ActorObject, BlueprintBody, CustomizationData, InstanceNames, LinearColor,
+            ObjectHeaderType, ObjectProperty, ObjectType, Property, PropertyList, PropertyType,
+        },
+        header::{BodyHeaderVersion, Header, Recipe, RecipeList, Resource, ResourceList},
+    },
+    transform::Transform,
+};
+
+/// Versions written by the game build the blueprints in this repository were saved with
+const HEADER_VERSION: u32 = 2;
+const SAVE_VERSION: u32 = 46;
+const BUILD_VERSION: u32 = 368883;
+
+/// Size of the first blueprint designer, in foundations
+const DEFAULT_SIZE: [u32; 3] = [4, 4, 4];
+
+/// Paint of a building.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Colour {
+    /// One of the swatch slots of the colour gun, 0 being the default
+    Swatch(u32),
+    /// A custom primary colour
+    Custom(LinearColor),
+}
+
+impl Default for Colour {
+    fn default() -> Self {
+        Colour::Swatch(0)
+    }
+}
+
+fn object_property(name: &str, reference: String) -> Property {
+    Property {
+        name: name.to_owned(),
+        property: PropertyType::ObjectProperty(ObjectProperty {
+            index: 0,
+            level_name: String::new(),
+            reference,
+        }),
+    }
+}
+
+/// The `mCustomizationData` property painting a building with `colour`.
+pub fn customization_data(colour: Colour) -> Property {
+    let mut customization = CustomizationData::default();
+    match colour {
+        Colour::Swatch(slot) => customization.set_swatch_slot(slot),
+        Colour::Custom(primary) => customization.set_custom_colors(primary, None),
+    }
+
+    let mut properties = PropertyList(Vec::new());
+    customization
+        .write(&mut properties)
+        .expect("Customization data should be written to an empty list");
+
+    properties.0.remove(0)
+}
+
+/// Builds a blueprint from scratch, one actor at a time. Paths include the trailing nul like the
+/// rest of the crate.
+#[derive(Debug, Clone)]
+pub struct BlueprintBuilder<'header> {
+    header: Header<'header>,
+    body: BlueprintBody,
+    names: InstanceNames,
+}
+
+impl Default for BlueprintBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'header> BlueprintBuilder<'header> {
+    pub fn new() -> Self {
+        let body = BlueprintBody::default();
+
+        BlueprintBuilder {
+            header: Header {
+                maybe_header_version: HEADER_VERSION,
+                maybe_save_version: SAVE_VERSION,
+                maybe_build_version: BUILD_VERSION,
+                blueprint_size: DEFAULT_SIZE,
+                resource_list: ResourceList::default(),
+                recipie_list: RecipeList::default(),
+                header_version: BodyHeaderVersion::V2,
+            },
+            names: body.instance_names(),
+            body,
+        }
+    }
+
+    /// Sets the size of the blueprint in foundations.
+    pub fn size(mut self, blueprint_size: [u32; 3]) -> Self {
+        self.header.blueprint_size = blueprint_size;
+        self
+    }
+
+    /// Sets the cost of the blueprint in the item at `path`, overriding the amount
+    /// [`BlueprintBuilder::build_with`] derives from the recipes. Repeated calls add up.
+    pub fn resource(mut self, path: &'header str, count: u32) -> Self {
+        let resources = &mut self.header.resource_list.resources;
+        match resources.iter_mut().find(|resource| resource.path == path) {
+            Some(resource) => resource.count += count,
+            None => resources.push(Resource { path, count }),
+        }
+
+        self
+    }
+
+    /// Adds a building of class `type_path` built with `recipe`. Its components are left for the
+    /// game to create.
+    pub fn actor(
+        mut self,
+        type_path: &str,
+        recipe: &'header str,
+        transform: &Transform,
+        colour: Colour,
+    ) -> Self {
+        let header = ActorHeader::new(type_path, self.names.allocate(type_path), transform);
+
+        let actor = ActorObject::buildable(PropertyList(vec![
+            customization_data(colour),
+            object_property("mBuiltWithRecipe\0", recipe.to_owned()),
+        ]));
+
+        self.body
+            .object_headers
+            .push(ObjectHeaderType::Actor(header));
+        self.body.objects.push(ObjectType::Actor(actor));
+
+        let recipe = Recipe(recipe);
+        if !self.header.recipie_list.recipies.contains(&recipe) {
+            self.header.recipie_list.recipies.push(recipe);
+        }
+
+        self
+    }
+
+    /// Builds the blueprint with only the resources set by [`BlueprintBuilder::resource`].
+    pub fn build(self) -> Blueprint<'header> {
+        Blueprint {
+            header: self.header,
+            body: self.body,
+        }
+    }
+
+    /// Builds the blueprint with the resource list derived from the recipes of its buildings in
+    /// `catalog`, see [`Blueprint::recompute_resources`]. Resources set by
+    /// [`BlueprintBuilder::resource`] replace the derived amount of their item.
+    pub fn build_with(self, catalog: &'header Catalog) -> Blueprint<'header> {
+        let overrides = self.header.resource_list.resources.clone();
+        let mut blueprint = self.build();
+        blueprint.recompute_resources(catalog);
+
+        let resources = &mut blueprint.header.resource_list.resources;
+        for resource in overrides {
+            match resources.iter_mut().find(|r| r.path == resource.path) {
+                Some(derived) => derived.count = resource.count,
+                None => resources.push(resource),
+            }
+        }
+
+        blueprint
+    }
+}
+
+impl<'header> Blueprint<'header> {
+    pub fn builder() -> BlueprintBuilder<'header> {
+        BlueprintBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{bp_write::BPWrite, transform::Vec3};
+
+    #[test]
+    fn check_builder() {
+        const BEAM: &str =
+            "/Game/FactoryGame/Prototype/Buildable/Beams/Build_Beam_Painted.Build_Beam_Painted_C\0";
+        const RECIPE: &str = "/Game/FactoryGame/Prototype/Buildable/Beams/Recipe_Beam_Painted.Recipe_Beam_Painted_C\0";
+        const STEEL: &str =
+            "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0";
+
+        let colour = Colour::Custom(LinearColor {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+            a: 1.0,
+        });
+        let blueprint = Blueprint::builder()
+            .size([5, 5, 5])
+            .actor(BEAM, RECIPE, &Transform::IDENTITY, Colour::default())
+            .actor(
+                BEAM,
+                RECIPE,
+                &Transform::from_translation(Vec3::new(0.0, 800.0, 0.0)),
+                colour,
+            )
+            .resource(STEEL, 2)
+            .resource(STEEL, 2)
+            .build();
+
+        assert_eq!(blueprint.body.validate_names(), []);
+        assert_eq!(blueprint.header.recipie_list.recipies, [Recipe(RECIPE)]);
+        assert_eq!(
+            blueprint.header.resource_list.resources,
+            [Resource {
+                path: STEEL,
+                count: 4
+            }]
+        );
+
+        let body = blueprint.body.clone();
+        let mut buf = Cursor::new(Vec::new());
+        blueprint.bp_write(&mut buf).expect("Write should succeed");
+
+        let parsed = Blueprint::new(buf.get_ref().as_slice()).expect("Parse should succeed");
+        assert_eq!(parsed.header.blueprint_size, [5, 5, 5]);
+        assert_eq!(parsed.body, body);
+    }
+
+    #[test]
+    fn check_build_with() {
+        const DOCS: &str = include_str!("../../Docs Sample.json");
+        const GENERATOR: &str = "/Game/FactoryGame/Buildable/Factory/GeneratorCoal/Build_GeneratorCoal.Build_GeneratorCoal_C\0";
+        const RECIPE: &str =
+            "/Game/FactoryGame/Recipes/Buildings/Recipe_GeneratorCoal.Recipe_GeneratorCoal_C\0";
+        const CABLE: &str = "/Game/FactoryGame/Resource/Parts/Cable/Desc_Cable.Desc_Cable_C\0";
+        const ROTOR: &str = "/Game/FactoryGame/Resource/Parts/Rotor/Desc_Rotor.Desc_Rotor_C\0";
+        const STEEL: &str =
+            "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0";
+
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+        let blueprint = Blueprint::builder()
+            .actor(GENERATOR, RECIPE, &Transform::IDENTITY, Colour::default())
+            .actor(
+                GENERATOR,
+                RECIPE,
+                &Transform::from_translation(Vec3::new(0.0, 1600.0, 0.0)),
+                Colour::default(),
+            )
+            .resource(ROTOR, 5)
+            .build_with(&catalog);
+
+        // Two generators cost twice the recipe, apart from the overridden rotors
+        assert_eq!(
+            blueprint.header.resource_list.resources,
+            [
+                Resource {
+                    path: CABLE,
+                    count: 60
+                },
+                Resource {
+                    path: ROTOR,
+                    count: 5
+                },
+                Resource {
+                    path: STEEL,
+                    count: 40
+                }
+            ]
+        );
+    }
+}
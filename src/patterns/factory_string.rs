@@ -1,18 +1,17 @@
+use std::borrow::Cow;
 use std::io::Write;
-use std::ops::Index;
-use std::slice::SliceIndex;
 
 use winnow::error::{ContextError, ParserError, StrContext};
 
 use winnow::token::take;
 
-use winnow::binary::le_u32;
+use winnow::binary::{le_i32, le_u32};
 use winnow::{Bytes, Parser};
 
-use crate::bp_write::BPWrite;
-
 pub trait FStringExt {
     fn size(&self) -> u32;
+    /// Size when written with [`write_text_fstring`]
+    fn text_size(&self) -> u32;
 }
 
 impl FStringExt for str {
@@ -21,6 +20,16 @@ impl FStringExt for str {
             .try_into()
             .expect("Factory string too long")
     }
+
+    fn text_size(&self) -> u32 {
+        if self.is_ascii() {
+            return self.size();
+        }
+
+        (self.encode_utf16().count() * 2 + 4)
+            .try_into()
+            .expect("Factory string too long")
+    }
 }
 
 pub fn fstring<'d>(data: &mut &'d Bytes) -> winnow::Result<&'d str> {
@@ -35,9 +44,61 @@ pub fn fstring<'d>(data: &mut &'d Bytes) -> winnow::Result<&'d str> {
     Ok(content)
 }
 
+/// Parses a factory string holding user text. Unreal stores text that is not pure ASCII as
+/// UTF-16 with a negative length counting its code units, and anything else as single bytes.
+pub fn text_fstring<'d>(data: &mut &'d Bytes) -> winnow::Result<Cow<'d, str>> {
+    let length = le_i32
+        .context(StrContext::Label("string length"))
+        .parse_next(data)?;
+
+    if length >= 0 {
+        let content = take(length as usize)
+            .context(StrContext::Label("string content"))
+            .parse_next(data)?;
+
+        // Single byte strings are Latin-1, which only matches UTF-8 for ASCII
+        return Ok(match str::from_utf8(content) {
+            Ok(content) if content.is_ascii() => Cow::Borrowed(content),
+            _ => Cow::Owned(content.iter().map(|&byte| char::from(byte)).collect()),
+        });
+    }
+
+    let content = take(length.unsigned_abs() as usize * 2)
+        .context(StrContext::Label("wide string content"))
+        .parse_next(data)?;
+    let units = content
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+    let content = char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| ContextError::from_input(data))?;
+
+    Ok(Cow::Owned(content))
+}
+
+/// Writes a factory string holding user text, as UTF-16 unless it is pure ASCII, see
+/// [`text_fstring`].
+pub fn write_text_fstring<W: Write>(text: &str, writer: &mut W) -> Result<(), std::io::Error> {
+    if text.is_ascii() {
+        let length: u32 = text.len().try_into().expect("Factory string too long");
+        writer.write_all(&length.to_le_bytes())?;
+        return writer.write_all(text.as_bytes());
+    }
+
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let length: i32 = units.len().try_into().expect("Factory string too long");
+    writer.write_all(&(-length).to_le_bytes())?;
+    for unit in units {
+        writer.write_all(&unit.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
+    use crate::bp_write::BPWrite;
 
     #[test]
     fn check_fstring() {
@@ -63,4 +124,28 @@ pub(crate) mod test {
 
         assert_eq!(buf, DATA);
     }
+
+    #[test]
+    fn check_text_fstring() {
+        const DATA: [u8; 0x0E] = [
+            0xFB, 0xFF, 0xFF, 0xFF, 0x32, 0x00, 0x30, 0x00, 0xB0, 0x00, 0x43, 0x00, 0x00, 0x00,
+        ];
+        const TEXT: &str = "20°C\0";
+
+        let text = text_fstring(&mut Bytes::new(&DATA[..])).expect("Parser should succeed");
+        assert_eq!(text, TEXT);
+        assert_eq!(TEXT.text_size() as usize, DATA.len());
+
+        let mut buf = Vec::new();
+        write_text_fstring(TEXT, &mut buf).expect("Serialization should succeed");
+        assert_eq!(buf, DATA);
+
+        // ASCII text keeps the single byte form and Latin-1 bytes are decoded as such
+        let mut buf = Vec::new();
+        write_text_fstring("Coal\0", &mut buf).expect("Serialization should succeed");
+        assert_eq!(buf, [0x05, 0x00, 0x00, 0x00, 0x43, 0x6F, 0x61, 0x6C, 0x00]);
+        let latin = [0x03, 0x00, 0x00, 0x00, 0xFC, 0x62, 0x00];
+        let text = text_fstring(&mut Bytes::new(&latin[..])).expect("Parser should succeed");
+        assert_eq!(text, "üb\0");
+    }
 }
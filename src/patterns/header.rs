@@ -15,6 +15,9 @@ use winnow::{
 
 use crate::bp_write::BPWrite;
 
+/// Side length of a foundation in unreal units, the unit of [`Header::blueprint_size`]
+pub const FOUNDATION_SIZE: f64 = 800.0;
+
 const MAGIC_NUM: u32 = 0x9E2A83C1;
 const MAX_CHUNK_SIZE: u32 = 128 * 1024;
 
@@ -71,10 +71,11 @@ mod tests {
             0x5F, 0x30, 0x31, 0x5F, 0x43, 0x00,
         ];
 
-        let first = Recipe("/Game/FactoryGame/Prototype/Buildable/Beams/Recipe_Beam_Painted.Recipe_Beam_Painted_C\0".into());
+        let first = Recipe(
+            "/Game/FactoryGame/Prototype/Buildable/Beams/Recipe_Beam_Painted.Recipe_Beam_Painted_C\0",
+        );
         let second = Recipe(
-            "/Game/FactoryGame/Recipes/Buildings/Walls/Recipe_Wall_8x4_01.Recipe_Wall_8x4_01_C\0"
-                .into(),
+            "/Game/FactoryGame/Recipes/Buildings/Walls/Recipe_Wall_8x4_01.Recipe_Wall_8x4_01_C\0",
         );
 
         let recipies = recipe_list
@@ -106,9 +106,18 @@ mod tests {
 
         let list = ResourceList {
             resources: vec![
-                Resource { path: "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0".into(), count: 2 },
-                Resource { path: "/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C\0".into(), count: 2 },
-                Resource { path: "/Game/FactoryGame/Resource/Parts/Cement/Desc_Cement.Desc_Cement_C\0".into(), count: 2 },
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/SteelPlate/Desc_SteelPlate.Desc_SteelPlate_C\0",
+                    count: 2,
+                },
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/IronPlate/Desc_IronPlate.Desc_IronPlate_C\0",
+                    count: 2,
+                },
+                Resource {
+                    path: "/Game/FactoryGame/Resource/Parts/Cement/Desc_Cement.Desc_Cement_C\0",
+                    count: 2,
+                },
             ],
         };
 
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    catalog::{Building, Catalog, Item, ItemAmount, RecipeData},
+    patterns::body::{BlueprintBody, ObjectHeaderType, ObjectType},
+};
+
+/// Recipe a production building is set to
+const CURRENT_RECIPE: &str = "mCurrentRecipe";
+/// Clock speed of a building, 1.0 being 100%
+const CURRENT_POTENTIAL: &str = "mCurrentPotential";
+const IS_PRODUCTION_PAUSED: &str = "mIsProductionPaused";
+
+/// Exponent the game uses for buildings that don't set their own
+const DEFAULT_POWER_EXPONENT: f64 = 1.321928;
+
+/// Recipes, buildings and items used by [`ProductionReport`]. [`Catalog`] provides the game's own, other
+/// tables can add modded or made up recipes.
+pub trait RecipeTable {
+    /// Looks up a recipe by its path, including the trailing nul.
+    fn recipe(&self, recipe: &str) -> Option<&RecipeData>;
+    /// Looks up a building by its type path, including the trailing nul.
+    fn building(&self, type_path: &str) -> Option<&Building>;
+    /// Looks up an item by its class name, for the energy value of generator fuels.
+    fn item(&self, item: &str) -> Option<&Item>;
+}
+
+impl RecipeTable for Catalog {
+    fn recipe(&self, recipe: &str) -> Option<&RecipeData> {
+        Catalog::recipe(self, recipe)
+    }
+
+    fn building(&self, type_path: &str) -> Option<&Building> {
+        Catalog::building(self, type_path)
+    }
+
+    fn item(&self, item: &str) -> Option<&Item> {
+        Catalog::item(self, item)
+    }
+}
+
+/// What one building consumes and produces, in items per minute and megawatts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildingProduction {
+    pub instance_name: String,
+    pub type_path: String,
+    pub recipe: Option<String>,
+    /// Clock speed, 1.0 being 100%
+    pub clock_speed: f64,
+    pub inputs: Vec<ItemAmount>,
+    pub outputs: Vec<ItemAmount>,
+    pub power_consumption: f64,
+    pub power_production: f64,
+}
+
+/// Production of every building in a blueprint running at its clock speed. Blueprints don't store
+/// the fuel of generators, which are counted as burning the first fuel they accept.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProductionReport {
+    pub buildings: Vec<BuildingProduction>,
+    /// Items per minute produced minus consumed by item class name, negative for a deficit
+    pub net: Vec<ItemAmount>,
+    /// Recipes set on buildings but missing from the table, sorted. Their buildings add nothing
+    /// to `net`, which is incomplete unless this is empty.
+    pub unresolved: Vec<String>,
+    pub power_consumption: f64,
+    pub power_production: f64,
+}
+
+impl ProductionReport {
+    /// Analyses the buildings of `body` that have a recipe or use power according to `table`.
+    /// Paused buildings are left out.
+    pub fn new(body: &BlueprintBody, table: &impl RecipeTable) -> Self {
+        let mut report = ProductionReport::default();
+        let mut net = BTreeMap::<String, f64>::new();
+        let mut unresolved = BTreeSet::new();
+
+        for (header, object) in body.object_headers.iter().zip(&body.objects) {
+            let (ObjectHeaderType::Actor(header), ObjectType::Actor(actor)) = (header, object)
+            else {
+                continue;
+            };
+            if actor.get::<bool>(IS_PRODUCTION_PAUSED) == Some(true) {
+                continue;
+            }
+
+            let recipe_path = actor.get::<&str>(CURRENT_RECIPE);
+            let recipe = recipe_path.and_then(|recipe| table.recipe(recipe));
+            let building = table.building(&header.type_path);
+            let consumption = building.and_then(|building| building.power_consumption);
+            let production = building.and_then(|building| building.power_production);
+            if recipe_path.is_none() && consumption.is_none() && production.is_none() {
+                continue;
+            }
+
+            let clock_speed = actor.get::<f32>(CURRENT_POTENTIAL).map_or(1.0, f64::from);
+            let per_minute = |amounts: &[ItemAmount], duration: f64| {
+                amounts
+                    .iter()
+                    .map(|amount| ItemAmount {
+                        item: amount.item.clone(),
+                        amount: amount.amount * 60.0 / duration * clock_speed,
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (inputs, outputs) = match (recipe_path, recipe, building) {
+                (_, Some(recipe), _) if recipe.duration > 0.0 => (
+                    per_minute(&recipe.ingredients, recipe.duration),
+                    per_minute(&recipe.products, recipe.duration),
+                ),
+                (Some(recipe_path), None, _) => {
+                    unresolved.insert(recipe_path.to_owned());
+                    (Vec::new(), Vec::new())
+                }
+                (None, _, Some(building)) => fuel_per_minute(building, table, clock_speed),
+                _ => (Vec::new(), Vec::new()),
+            };
+            for input in &inputs {
+                *net.entry(input.item.clone()).or_default() -= input.amount;
+            }
+            for output in &outputs {
+                *net.entry(output.item.clone()).or_default() += output.amount;
+            }
+
+            let exponent = building
+                .and_then(|building| building.power_consumption_exponent)
+                .unwrap_or(DEFAULT_POWER_EXPONENT);
+            let production = BuildingProduction {
+                instance_name: header.instance_name.clone(),
+                type_path: header.type_path.clone(),
+                recipe: recipe_path.map(ToOwned::to_owned),
+                clock_speed,
+                inputs,
+                outputs,
+                power_consumption: consumption.unwrap_or_default() * clock_speed.powf(exponent),
+                power_production: production.unwrap_or_default() * clock_speed,
+            };
+            report.power_consumption += production.power_consumption;
+            report.power_production += production.power_production;
+            report.buildings.push(production);
+        }
+
+        report.net = net
+            .into_iter()
+            .map(|(item, amount)| ItemAmount { item, amount })
+            .collect();
+        report.unresolved = unresolved.into_iter().collect();
+        report
+    }
+
+    /// Megawatts produced minus consumed, negative when the blueprint needs more power.
+    pub fn net_power(&self) -> f64 {
+        self.power_production - self.power_consumption
+    }
+
+    /// Items produced faster than they are consumed.
+    pub fn surplus(&self) -> impl Iterator<Item = &ItemAmount> {
+        self.net.iter().filter(|amount| amount.amount > 0.0)
+    }
+
+    /// Items consumed faster than they are produced, with positive amounts.
+    pub fn deficit(&self) -> impl Iterator<Item = ItemAmount> {
+        self.net
+            .iter()
+            .filter(|amount| amount.amount < 0.0)
+            .map(|amount| ItemAmount {
+                item: amount.item.clone(),
+                amount: -amount.amount,
+            })
+    }
+}
+
+/// Items per minute a generator burns and leaves behind running on its first fuel at
+/// `clock_speed`. Buildings without fuel or an energy value for it burn nothing.
+fn fuel_per_minute(
+    building: &Building,
+    table: &impl RecipeTable,
+    clock_speed: f64,
+) -> (Vec<ItemAmount>, Vec<ItemAmount>) {
+    let (Some(fuel), Some(power)) = (building.fuels.first(), building.power_production) else {
+        return (Vec::new(), Vec::new());
+    };
+    let power = power * clock_speed;
+
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    // Energy values are in megajoules, so a megawatt burns one per second
+    let burnt = table
+        .item(&fuel.item)
+        .and_then(|item| item.energy_value)
+        .filter(|energy| *energy > 0.0)
+        .map(|energy| power * 60.0 / energy);
+    if let Some(burnt) = burnt {
+        inputs.push(ItemAmount {
+            item: fuel.item.clone(),
+            amount: burnt,
+        });
+        if let Some(byproduct) = &fuel.byproduct {
+            outputs.push(ItemAmount {
+                item: byproduct.item.clone(),
+                amount: byproduct.amount * burnt,
+            });
+        }
+    }
+    if let (Some(supplemental), Some(ratio)) =
+        (&fuel.supplemental, building.supplemental_to_power_ratio)
+    {
+        inputs.push(ItemAmount {
+            item: supplemental.clone(),
+            amount: power * ratio * 60.0,
+        });
+    }
+
+    (inputs, outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::Blueprint;
+
+    const DATA: &[u8] = include_bytes!("../Coal Left to Right.sbp");
+    const DOCS: &str = include_str!("../Docs Sample.json");
+    const CONSTRUCTOR: &str = "/Game/FactoryGame/Buildable/Factory/ConstructorMk1/Build_ConstructorMk1.Build_ConstructorMk1_C\0";
+    const CABLE: &str = "/Game/FactoryGame/Recipes/Recipe_Cable.Recipe_Cable_C\0";
+
+    #[test]
+    fn check_generators() {
+        let body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+
+        let report = ProductionReport::new(&body, &catalog);
+        assert_eq!(report.buildings.len(), 3);
+        assert_eq!(report.power_production, 225.0);
+        assert_eq!(report.net_power(), 225.0);
+
+        // Each generator burns 15 coal and 45000 litres of water a minute
+        assert_eq!(
+            report.deficit().collect::<Vec<_>>(),
+            [
+                ItemAmount {
+                    item: "Desc_Coal_C".to_owned(),
+                    amount: 45.0
+                },
+                ItemAmount {
+                    item: "Desc_Water_C".to_owned(),
+                    amount: 135000.0
+                }
+            ]
+        );
+        assert_eq!(report.surplus().count(), 0);
+    }
+
+    #[test]
+    fn check_unresolved_recipe() {
+        const UNKNOWN: &str = "/Game/Unknown/Recipe_Unknown.Recipe_Unknown_C\0";
+
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+        assert!(ProductionReport::new(&body, &catalog).unresolved.is_empty());
+
+        // A recipe missing from the table is reported rather than treated as fuel
+        let ObjectType::Actor(actor) = &mut body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .insert(CURRENT_RECIPE, UNKNOWN)
+            .expect("Insert should succeed");
+
+        let report = ProductionReport::new(&body, &catalog);
+        assert_eq!(report.unresolved, [UNKNOWN]);
+        assert!(report.buildings[0].inputs.is_empty());
+        assert_eq!(
+            report
+                .deficit()
+                .find(|amount| amount.item == "Desc_Coal_C")
+                .map(|amount| amount.amount),
+            Some(30.0)
+        );
+    }
+
+    #[test]
+    fn check_overclocked_recipe() {
+        let mut body = Blueprint::new(DATA).expect("Parse should succeed").body;
+        let catalog = Catalog::from_json(DOCS).expect("Catalog should load");
+
+        // Turn the first generator into an overclocked cable constructor
+        let ObjectHeaderType::Actor(header) = &mut body.object_headers[0] else {
+            panic!("Expected an actor header");
+        };
+        header.type_path = CONSTRUCTOR.to_owned();
+        let ObjectType::Actor(actor) = &mut body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .insert(CURRENT_RECIPE, CABLE)
+            .expect("Insert should succeed");
+        actor
+            .insert(CURRENT_POTENTIAL, 2.0f32)
+            .expect("Insert should succeed");
+
+        let report = ProductionReport::new(&body, &catalog);
+        let constructor = &report.buildings[0];
+        assert_eq!(constructor.clock_speed, 2.0);
+        assert_eq!(constructor.inputs[0].amount, 120.0);
+        assert_eq!(constructor.outputs[0].amount, 60.0);
+        assert!((constructor.power_consumption - 10.0).abs() < 0.001);
+        assert_eq!(report.power_production, 150.0);
+
+        assert_eq!(
+            report.surplus().collect::<Vec<_>>(),
+            [&ItemAmount {
+                item: "Desc_Cable_C".to_owned(),
+                amount: 60.0
+            }]
+        );
+        assert_eq!(
+            report.deficit().collect::<Vec<_>>(),
+            [
+                ItemAmount {
+                    item: "Desc_Coal_C".to_owned(),
+                    amount: 30.0
+                },
+                ItemAmount {
+                    item: "Desc_Water_C".to_owned(),
+                    amount: 90000.0
+                },
+                ItemAmount {
+                    item: "Desc_Wire_C".to_owned(),
+                    amount: 120.0
+                }
+            ]
+        );
+
+        // Paused buildings don't produce anything
+        let ObjectType::Actor(actor) = &mut body.objects[0] else {
+            panic!("Expected an actor object");
+        };
+        actor
+            .insert(IS_PRODUCTION_PAUSED, true)
+            .expect("Insert should succeed");
+        let report = ProductionReport::new(&body, &catalog);
+        assert_eq!(report.surplus().count(), 0);
+        assert!(
+            report
+                .net
+                .iter()
+                .all(|amount| ["Desc_Coal_C", "Desc_Water_C"].contains(&amount.item.as_str()))
+        );
+    }
+}
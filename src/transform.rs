@@ -0,0 +1,505 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Vector in world units (centimetres), using Unreal's left handed, Z up axes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+    pub const ONE: Vec3 = Vec3::new(1.0, 1.0, 1.0);
+    pub const X: Vec3 = Vec3::new(1.0, 0.0, 0.0);
+    pub const Y: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+    pub const Z: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn dot(self, other: Vec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn distance(self, other: Vec3) -> f64 {
+        (self - other).length()
+    }
+
+    /// Returns a vector of length one pointing in the same direction, or zero if the vector is zero.
+    pub fn normalize(self) -> Vec3 {
+        let length = self.length();
+        if length == 0.0 {
+            return Vec3::ZERO;
+        }
+
+        self * (1.0 / length)
+    }
+
+    /// Component wise multiplication, used for applying scale.
+    pub fn scale(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    pub fn recip(self) -> Vec3 {
+        Vec3::new(1.0 / self.x, 1.0 / self.y, 1.0 / self.z)
+    }
+
+    /// Reflects the vector through the plane through the origin with the given unit normal.
+    pub fn mirror(self, normal: Vec3) -> Vec3 {
+        self - normal * (2.0 * self.dot(normal))
+    }
+
+    pub fn abs_diff_eq(self, other: Vec3, epsilon: f64) -> bool {
+        (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, rhs: Vec3) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, rhs: Vec3) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: f64) -> Vec3 {
+        Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+/// Axis aligned box, including its bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl BoundingBox {
+    /// Box between two opposite corners given in any order.
+    pub fn new(a: Vec3, b: Vec3) -> Self {
+        BoundingBox {
+            min: Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn contains(&self, point: Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+}
+
+/// Rotation in degrees, matching Unreal's `FRotator`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rotator {
+    pub yaw: f64,
+    pub pitch: f64,
+    pub roll: f64,
+}
+
+impl Rotator {
+    pub const fn new(yaw: f64, pitch: f64, roll: f64) -> Self {
+        Rotator { yaw, pitch, roll }
+    }
+}
+
+/// Rotation quaternion stored in the same x, y, z, w order as the blueprint file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::IDENTITY
+    }
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat::new(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Quat { x, y, z, w }
+    }
+
+    pub fn from_axis_angle(axis: Vec3, degrees: f64) -> Quat {
+        let axis = axis.normalize();
+        let (sin, cos) = (degrees.to_radians() / 2.0).sin_cos();
+
+        Quat::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Rotation about the vertical axis, the only rotation most buildings use.
+    pub fn from_yaw(degrees: f64) -> Quat {
+        Quat::from_axis_angle(Vec3::Z, degrees)
+    }
+
+    /// Same conversion as Unreal's `FRotator::Quaternion`.
+    pub fn from_rotator(rotator: Rotator) -> Quat {
+        let (sp, cp) = (rotator.pitch.to_radians() / 2.0).sin_cos();
+        let (sy, cy) = (rotator.yaw.to_radians() / 2.0).sin_cos();
+        let (sr, cr) = (rotator.roll.to_radians() / 2.0).sin_cos();
+
+        Quat {
+            x: cr * sp * sy - sr * cp * cy,
+            y: -cr * sp * cy - sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy,
+            w: cr * cp * cy + sr * sp * sy,
+        }
+    }
+
+    /// Same conversion as Unreal's `FQuat::Rotator`, including its handling of gimbal lock.
+    pub fn to_rotator(self) -> Rotator {
+        const SINGULARITY_THRESHOLD: f64 = 0.4999995;
+
+        let Quat { x, y, z, w } = self;
+        let singularity_test = z * x - w * y;
+        let yaw = (2.0 * (w * z + x * y))
+            .atan2(1.0 - 2.0 * (y * y + z * z))
+            .to_degrees();
+
+        if singularity_test < -SINGULARITY_THRESHOLD {
+            Rotator {
+                yaw,
+                pitch: -90.0,
+                roll: normalize_axis(-yaw - 2.0 * x.atan2(w).to_degrees()),
+            }
+        } else if singularity_test > SINGULARITY_THRESHOLD {
+            Rotator {
+                yaw,
+                pitch: 90.0,
+                roll: normalize_axis(yaw - 2.0 * x.atan2(w).to_degrees()),
+            }
+        } else {
+            Rotator {
+                yaw,
+                pitch: (2.0 * singularity_test).asin().to_degrees(),
+                roll: (-2.0 * (w * x + y * z))
+                    .atan2(1.0 - 2.0 * (x * x + y * y))
+                    .to_degrees(),
+            }
+        }
+    }
+
+    pub fn length(self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat {
+        let length = self.length();
+        if length == 0.0 {
+            return Quat::IDENTITY;
+        }
+
+        Quat::new(
+            self.x / length,
+            self.y / length,
+            self.z / length,
+            self.w / length,
+        )
+    }
+
+    /// Inverse of a unit quaternion.
+    pub fn inverse(self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Conjugates the rotation by the reflection through the plane with the given unit normal,
+    /// giving `M * R * M`. The result is still a proper rotation, so no negative scale is needed.
+    pub fn mirror(self, normal: Vec3) -> Quat {
+        // The rotation axis is a pseudovector so it flips the other way to a position
+        let axis = -Vec3::new(self.x, self.y, self.z).mirror(normal);
+
+        Quat::new(axis.x, axis.y, axis.z, self.w)
+    }
+
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+
+        v + t * self.w + q.cross(t)
+    }
+
+    /// Checks whether two quaternions describe the same rotation, treating `q` and `-q` as equal.
+    pub fn abs_diff_eq(self, other: Quat, epsilon: f64) -> bool {
+        let dot = self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w;
+
+        (1.0 - dot.abs()) <= epsilon
+    }
+}
+
+/// `a * b` applies `b` first and then `a`.
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, rhs: Quat) -> Quat {
+        Quat {
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        }
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        self.rotate(rhs)
+    }
+}
+
+impl From<Rotator> for Quat {
+    fn from(rotator: Rotator) -> Self {
+        Quat::from_rotator(rotator)
+    }
+}
+
+impl From<Quat> for Rotator {
+    fn from(quat: Quat) -> Self {
+        quat.to_rotator()
+    }
+}
+
+fn normalize_axis(degrees: f64) -> f64 {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees > 180.0 {
+        degrees - 360.0
+    } else {
+        degrees
+    }
+}
+
+/// Scale, then rotation, then translation, the same order Unreal applies an `FTransform` in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub rotation: Quat,
+    pub translation: Vec3,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Quat::IDENTITY,
+        translation: Vec3::ZERO,
+        scale: Vec3::ONE,
+    };
+
+    pub fn new(rotation: Quat, translation: Vec3, scale: Vec3) -> Self {
+        Transform {
+            rotation,
+            translation,
+            scale,
+        }
+    }
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Transform {
+            translation,
+            ..Transform::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Transform {
+            rotation,
+            ..Transform::IDENTITY
+        }
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation.rotate(point.scale(self.scale)) + self.translation
+    }
+
+    /// Like [`Transform::transform_point`] but ignores translation, for directions and tangents.
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.rotation.rotate(vector.scale(self.scale))
+    }
+
+    /// Exact for uniform scale; with non uniform scale and rotation the result is approximate,
+    /// as it is in Unreal.
+    pub fn inverse(&self) -> Transform {
+        let rotation = self.rotation.inverse();
+        let scale = self.scale.recip();
+        let translation = rotation.rotate(-self.translation).scale(scale);
+
+        Transform {
+            rotation,
+            translation,
+            scale,
+        }
+    }
+
+    pub fn abs_diff_eq(&self, other: &Transform, epsilon: f64) -> bool {
+        self.rotation.abs_diff_eq(other.rotation, epsilon)
+            && self.translation.abs_diff_eq(other.translation, epsilon)
+            && self.scale.abs_diff_eq(other.scale, epsilon)
+    }
+}
+
+/// `a * b` applies `b` first and then `a`.
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Transform {
+        Transform {
+            rotation: (self.rotation * rhs.rotation).normalize(),
+            translation: self.transform_point(rhs.translation),
+            scale: self.scale.scale(rhs.scale),
+        }
+    }
+}
+
+impl Mul<Vec3> for Transform {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        self.transform_point(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn check_rotator_round_trip() {
+        let rotator = Rotator::new(-90.0, 30.0, 10.0);
+        let quat = Quat::from_rotator(rotator);
+        let back = quat.to_rotator();
+
+        assert!((back.yaw - rotator.yaw).abs() < EPSILON);
+        assert!((back.pitch - rotator.pitch).abs() < EPSILON);
+        assert!((back.roll - rotator.roll).abs() < EPSILON);
+    }
+
+    #[test]
+    fn check_yaw_matches_file() {
+        // Rotation of the beam in Test.sbp
+        let quat = Quat::new(
+            0.0,
+            0.0,
+            -std::f64::consts::FRAC_1_SQRT_2,
+            std::f64::consts::FRAC_1_SQRT_2,
+        );
+
+        assert!(quat.abs_diff_eq(Quat::from_yaw(-90.0), EPSILON));
+        assert!((quat.to_rotator().yaw + 90.0).abs() < EPSILON);
+        assert!((quat * Vec3::X).abs_diff_eq(-Vec3::Y, EPSILON));
+    }
+
+    #[test]
+    fn check_transform_compose_and_inverse() {
+        let a = Transform::new(Quat::from_yaw(90.0), Vec3::new(100.0, 0.0, 0.0), Vec3::ONE);
+        let b = Transform::from_translation(Vec3::new(0.0, 800.0, 50.0));
+        let point = Vec3::new(10.0, 20.0, 30.0);
+
+        let composed = a * b;
+        assert!(
+            composed
+                .transform_point(point)
+                .abs_diff_eq(a.transform_point(b.transform_point(point)), EPSILON)
+        );
+
+        let identity = composed * composed.inverse();
+        assert!(identity.abs_diff_eq(&Transform::IDENTITY, EPSILON));
+        assert!(
+            composed
+                .inverse()
+                .transform_point(composed.transform_point(point))
+                .abs_diff_eq(point, EPSILON)
+        );
+    }
+
+    #[test]
+    fn check_transform_scale() {
+        let transform = Transform::new(Quat::from_yaw(180.0), Vec3::ZERO, Vec3::new(2.0, 2.0, 2.0));
+
+        let point = transform.transform_point(Vec3::new(1.0, 2.0, 3.0));
+        assert!(point.abs_diff_eq(Vec3::new(-2.0, -4.0, 6.0), EPSILON));
+        assert!(
+            transform
+                .inverse()
+                .transform_point(point)
+                .abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), EPSILON)
+        );
+    }
+
+    #[test]
+    fn check_mirror() {
+        let rotation = Quat::from_rotator(Rotator::new(30.0, 20.0, -10.0));
+        let point = Vec3::new(1.0, 2.0, 3.0);
+
+        for normal in [Vec3::X, Vec3::Y, Vec3::Z] {
+            let mirrored = rotation.mirror(normal);
+            let expected = rotation.rotate(point.mirror(normal)).mirror(normal);
+            assert!(mirrored.rotate(point).abs_diff_eq(expected, EPSILON));
+        }
+
+        assert!(
+            Quat::from_yaw(30.0)
+                .mirror(Vec3::Y)
+                .abs_diff_eq(Quat::from_yaw(-30.0), EPSILON)
+        );
+    }
+}